@@ -0,0 +1,123 @@
+// The IDL instructions generated by `#[program]` still call `AccountInfo::realloc`.
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+        state::Account as TokenAccountState,
+    },
+    Mint, TokenAccount,
+};
+use project_5_capstone::BettingPool;
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("2DUe9T8agLhgJb6WSsoqSxx5JQaMgc9gfY3s8871NEwK");
+
+/// Transfer hook for outcome mints of pools that opt in with `enable_transfer_hook`.
+/// Tokens trade freely while betting is open and after finalization, and are held
+/// in place in between so nobody can move a position out from under a dispute.
+#[program]
+pub mod outcome_transfer_hook {
+    use super::*;
+
+    /// Register the pool as the one extra account Token-2022 passes to `execute`.
+    /// Called by the betting pool program from `initialize_outcome`.
+    pub fn initialize_extra_account_metas(ctx: Context<InitializeExtraAccountMetas>) -> Result<()> {
+        let metas = [ExtraAccountMeta::new_with_pubkey(&ctx.accounts.pool.key(), false, false)?];
+        let mut data = ctx.accounts.extra_account_metas.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+
+        Ok(())
+    }
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn execute(ctx: Context<Execute>, _amount: u64) -> Result<()> {
+        // Only Token-2022 sets the flag, so the hook cannot be called on its own
+        let source = ctx.accounts.source_token.to_account_info();
+        let data = source.try_borrow_data()?;
+        let account = StateWithExtensions::<TokenAccountState>::unpack(&data)?;
+        require!(
+            bool::from(account.get_extension::<TransferHookAccount>()?.transferring),
+            TransferHookError::NotTransferring
+        );
+
+        let pool = &ctx.accounts.pool;
+        let mint = ctx.accounts.mint.key();
+        require!(
+            pool.outcomes.iter().any(|outcome| outcome.mint == mint),
+            TransferHookError::NotAnOutcomeMint
+        );
+
+        require!(!pool.is_disputed, TransferHookError::DisputeOpen);
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time < pool.end_time || pool.is_finalized,
+            TransferHookError::BettingEnded
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetas<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Only the betting pool program can sign for its pools
+    pub pool: Signer<'info>,
+
+    #[account(
+        extensions::transfer_hook::authority = pool,
+        extensions::transfer_hook::program_id = crate::ID
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: created here and filled in as an ExtraAccountMetaList
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(1)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_metas: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner or delegate of the source account, checked by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: the list Token-2022 resolved the pool from
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_metas: UncheckedAccount<'info>,
+
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[error_code]
+pub enum TransferHookError {
+    #[msg("The hook was not called by a Token-2022 transfer")]
+    NotTransferring,
+    #[msg("Mint is not an outcome of this pool")]
+    NotAnOutcomeMint,
+    #[msg("Outcome tokens cannot move while a dispute is open")]
+    DisputeOpen,
+    #[msg("Outcome tokens cannot move between the end of betting and finalization")]
+    BettingEnded,
+}
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+
+/// A pool was created. Its outcomes are added afterwards by `initialize_outcome`.
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub market_id: u64,
+    pub collateral_mint: Pubkey,
+    pub outcome_count: u8,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+/// `amount` collateral was deposited for as many tokens of `outcome`
+#[event]
+pub struct TokensMinted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// `amount` tokens of `outcome` were burned for as much collateral
+#[event]
+pub struct TokensBurned {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// The operator proposed an answer, opening the dispute period. Categorical
+/// markets set `winner`, scalar markets `value`.
+#[event]
+pub struct SolutionProposed {
+    pub pool: Pubkey,
+    pub proposer: Pubkey,
+    pub winner: Option<u8>,
+    pub value: Option<i64>,
+    pub dispute_period_end: i64,
+    pub timestamp: i64,
+}
+
+/// A holder of `outcome` disputed the proposed answer with `tokens` and escrowed
+/// `bond`. Rounds count from 0.
+#[event]
+pub struct SolutionDisputed {
+    pub pool: Pubkey,
+    pub disputer: Pubkey,
+    pub outcome: u8,
+    pub tokens: u64,
+    pub bond: u64,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// A dispute round was decided, by the operator or a jury. `upheld` is set when
+/// the proposed answer stood.
+#[event]
+pub struct DisputeResolved {
+    pub pool: Pubkey,
+    pub winner: Option<u8>,
+    pub value: Option<i64>,
+    pub upheld: bool,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// The answer is final and claims are open. Oracle-resolved pools skip straight to
+/// this after `OracleResolved`.
+#[event]
+pub struct PoolFinalized {
+    pub pool: Pubkey,
+    pub winner: Option<u8>,
+    pub value: Option<i64>,
+    pub timestamp: i64,
+}
+
+/// `tokens` of `outcome` were burned for `payout` collateral after `fees` were held back
+#[event]
+pub struct WinningsClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub tokens: u64,
+    pub payout: u64,
+    pub fees: u64,
+    pub timestamp: i64,
+}
+
+/// A finished pool was closed. `swept` collateral went to the treasury and the
+/// rent back to the creator.
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub swept: u64,
+    pub timestamp: i64,
+}
+
+/// `amount` collateral was deposited for `amount` tokens of every outcome
+#[event]
+pub struct CompleteSetsMinted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// `amount` tokens of every outcome were burned for `payout` collateral, which is
+/// `amount` until the pool is finalized
+#[event]
+pub struct CompleteSetsRedeemed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub payout: u64,
+    pub timestamp: i64,
+}
+
+/// The operator seeded the market maker with `liquidity` complete sets
+#[event]
+pub struct AmmFunded {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub liquidity: u64,
+    pub timestamp: i64,
+}
+
+/// `amount` tokens of `outcome` were bought from the market maker for `cost`
+#[event]
+pub struct OutcomeTokensBought {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub cost: u64,
+    pub timestamp: i64,
+}
+
+/// `amount` tokens of `outcome` were sold to the market maker for `proceeds`
+#[event]
+pub struct OutcomeTokensSold {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub proceeds: u64,
+    pub timestamp: i64,
+}
+
+/// The oracle price the pool was resolved with, emitted just before `PoolFinalized`
+#[event]
+pub struct OracleResolved {
+    pub pool: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub publish_time: i64,
+    pub timestamp: i64,
+}
+
+/// The operator took back the `amount` of its proposer bond that survived the disputes
+#[event]
+pub struct ProposerBondWithdrawn {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// `recipient` withdrew `amount` in fees, creator fees for the operator and
+/// protocol fees for the treasury
+#[event]
+pub struct FeesWithdrawn {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// The operator claimed `payout` collateral for the market maker's reserves
+#[event]
+pub struct AmmLiquidityClaimed {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub payout: u64,
+    pub timestamp: i64,
+}
+
+/// `new_operator` accepted the pool's operator role from `previous_operator`
+#[event]
+pub struct AuthorityTransferred {
+    pub pool: Pubkey,
+    pub previous_operator: Pubkey,
+    pub new_operator: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::math::{bps_of, Rounding};
+use crate::{BettingPoolError, INVALID_OUTCOME, MAX_OUTCOMES};
+
+/// Maximum number of jurors staked in the registry at once
+pub const MAX_JURORS: usize = 32;
+/// Maximum number of jurors drawn for a single dispute
+pub const MAX_JURY_SIZE: usize = 9;
+/// Slots between opening a dispute and the first slot whose hash can seed its jury
+pub const JURY_DRAW_DELAY_SLOTS: u64 = 8;
+/// Slots each seed slot stays in use. Well under the 512 slots the SlotHashes
+/// sysvar keeps, so a late draw always finds its seed.
+pub const JURY_DRAW_WINDOW_SLOTS: u64 = 256;
+/// Time after a dispute is opened from which the pool's operator can resolve it,
+/// if no jury has been drawn by then
+pub const JURY_DRAW_TIMEOUT_SECONDS: i64 = 3 * 24 * 60 * 60;
+
+/// Jurors and their governance token stakes, shared by every jury-resolved pool
+#[account]
+pub struct JuryRegistry {
+    pub governance_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub min_stake: u64, // needed to be drawn
+    pub jury_size: u8,
+    pub commit_period_seconds: i64,
+    pub reveal_period_seconds: i64,
+    pub slash_bps: u64, // share of stake lost by jurors outside the majority
+    pub jurors: Vec<JurorEntry>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct JurorEntry {
+    pub juror: Pubkey,
+    pub stake: u64,
+    pub open_panels: u8, // stake is locked while this is non-zero
+}
+
+/// The jurors drawn for the current dispute of a pool and their votes
+#[account]
+pub struct JuryPanel {
+    pub pool: Pubkey,
+    pub payer: Pubkey, // receives the rent back once the votes are tallied
+    pub commit_deadline: i64,
+    pub reveal_deadline: i64,
+    pub seats: Vec<JurySeat>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct JurySeat {
+    pub juror: Pubkey,
+    pub commitment: Option<[u8; 32]>,
+    pub vote: Option<u8>,
+}
+
+impl JurorEntry {
+    pub const SPACE: usize = 32 + // juror: Pubkey
+        8 + // stake: u64
+        1; // open_panels: u8
+}
+
+impl JurySeat {
+    pub const SPACE: usize = 32 + // juror: Pubkey
+        1 + 32 + // commitment: Option<[u8; 32]>
+        1 + 1; // vote: Option<u8>
+}
+
+impl JuryRegistry {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // governance_mint: Pubkey
+        32 + // stake_vault: Pubkey
+        8 +  // min_stake: u64
+        1 +  // jury_size: u8
+        8 +  // commit_period_seconds: i64
+        8 +  // reveal_period_seconds: i64
+        8 +  // slash_bps: u64
+        4 + MAX_JURORS * JurorEntry::SPACE + // jurors: Vec<JurorEntry>
+        1; // bump: u8
+
+    pub fn entry_mut(&mut self, juror: &Pubkey) -> Result<&mut JurorEntry> {
+        self.jurors
+            .iter_mut()
+            .find(|entry| entry.juror == *juror)
+            .ok_or_else(|| error!(BettingPoolError::NotAJuror))
+    }
+
+    /// Draw `jury_size` distinct jurors with at least `min_stake`, skipping
+    /// `excluded`, and lock their stakes until the panel is tallied. The draw is a
+    /// shuffle seeded by `seed`.
+    pub fn draw_panel(&mut self, seed: [u8; 32], excluded: &[Pubkey]) -> Result<Vec<JurySeat>> {
+        let mut eligible: Vec<usize> = (0..self.jurors.len())
+            .filter(|&index| {
+                let entry = &self.jurors[index];
+                entry.stake >= self.min_stake && !excluded.contains(&entry.juror)
+            })
+            .collect();
+        let jury_size = self.jury_size as usize;
+        require!(eligible.len() >= jury_size, BettingPoolError::NotEnoughJurors);
+
+        let mut seats = Vec::with_capacity(jury_size);
+        for position in 0..jury_size {
+            let hash = hashv(&[&seed, &(position as u64).to_le_bytes()]).to_bytes();
+            let mut draw = [0u8; 8];
+            draw.copy_from_slice(&hash[..8]);
+            let draw = u64::from_le_bytes(draw);
+            let pick = position + (draw % (eligible.len() - position) as u64) as usize;
+            eligible.swap(position, pick);
+
+            let entry = &mut self.jurors[eligible[position]];
+            entry.open_panels = entry.open_panels.checked_add(1).ok_or(BettingPoolError::MathOverflow)?;
+            seats.push(JurySeat {
+                juror: entry.juror,
+                commitment: None,
+                vote: None,
+            });
+        }
+
+        Ok(seats)
+    }
+
+    /// Unlock the panel's jurors once `winner` is decided. Jurors who voted for
+    /// something else or never revealed lose `slash_bps` of their stake, split
+    /// evenly between those who voted for `winner`.
+    pub fn settle_panel(&mut self, panel: &JuryPanel, winner: u8) -> Result<()> {
+        let majority = panel.seats.iter().filter(|seat| seat.vote == Some(winner)).count() as u64;
+        let slash_bps = self.slash_bps;
+
+        let mut slashed = 0u64;
+        for seat in &panel.seats {
+            let entry = self.entry_mut(&seat.juror)?;
+            entry.open_panels = entry.open_panels.checked_sub(1).ok_or(BettingPoolError::MathOverflow)?;
+
+            // Nobody is slashed when no one voted with the result
+            if majority > 0 && seat.vote != Some(winner) {
+                let penalty = bps_of(entry.stake, slash_bps, Rounding::Down)?;
+                entry.stake -= penalty;
+                slashed = slashed.checked_add(penalty).ok_or(BettingPoolError::MathOverflow)?;
+            }
+        }
+
+        // The first majority juror also takes the rounding remainder
+        let mut remainder = slashed.checked_rem(majority).unwrap_or(0);
+        for seat in panel.seats.iter().filter(|seat| seat.vote == Some(winner)) {
+            let entry = self.entry_mut(&seat.juror)?;
+            let reward = slashed / majority + remainder;
+            entry.stake = entry.stake.checked_add(reward).ok_or(BettingPoolError::MathOverflow)?;
+            remainder = 0;
+        }
+
+        Ok(())
+    }
+}
+
+impl JuryPanel {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // pool: Pubkey
+        32 + // payer: Pubkey
+        8 +  // commit_deadline: i64
+        8 +  // reveal_deadline: i64
+        4 + MAX_JURY_SIZE * JurySeat::SPACE + // seats: Vec<JurySeat>
+        1; // bump: u8
+
+    pub fn seat_mut(&mut self, juror: &Pubkey) -> Result<&mut JurySeat> {
+        self.seats
+            .iter_mut()
+            .find(|seat| seat.juror == *juror)
+            .ok_or_else(|| error!(BettingPoolError::NotAJuror))
+    }
+
+    /// The outcome with the most revealed votes. Without a strict plurality the
+    /// `proposed` outcome stands.
+    pub fn verdict(&self, proposed: u8) -> u8 {
+        // Votes for INVALID_OUTCOME are counted in the last slot
+        let slot = |outcome: u8| (outcome as usize).min(MAX_OUTCOMES);
+        let mut votes = [0u8; MAX_OUTCOMES + 1];
+        for vote in self.seats.iter().filter_map(|seat| seat.vote) {
+            votes[slot(vote)] += 1;
+        }
+
+        let most = votes.iter().copied().max().unwrap_or(0);
+        let mut leaders = (0..MAX_OUTCOMES as u8)
+            .chain([INVALID_OUTCOME])
+            .filter(|&outcome| votes[slot(outcome)] == most);
+        match (leaders.next(), leaders.next()) {
+            (Some(outcome), None) if most > 0 => outcome,
+            _ => proposed,
+        }
+    }
+}
+
+/// Hash a juror commits to before revealing `outcome` and `salt`. Binding the
+/// juror's key stops others from copying a commitment.
+pub fn vote_commitment(juror: &Pubkey, outcome: u8, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[juror.as_ref(), &[outcome], salt]).to_bytes()
+}
+
+/// Seed for the jury of the dispute opened at `dispute_slot`: the hash of the first
+/// block at or after a seed slot nobody knew when the dispute was opened. A draw
+/// more than a window after the first seed slot moves on to a later one, but each
+/// seed slot stays fixed for its whole window, so the caller cannot pick the jury
+/// by choosing when to draw.
+pub fn jury_seed(pool: &Pubkey, dispute_slot: u64, current_slot: u64, slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let first_seed_slot = dispute_slot
+        .checked_add(JURY_DRAW_DELAY_SLOTS)
+        .ok_or(BettingPoolError::MathOverflow)?;
+    require!(current_slot > first_seed_slot, BettingPoolError::JuryDrawTooEarly);
+    let windows = (current_slot - 1 - first_seed_slot) / JURY_DRAW_WINDOW_SLOTS;
+    let seed_slot = first_seed_slot + windows * JURY_DRAW_WINDOW_SLOTS;
+
+    // SlotHashes is a u64 length followed by (slot, hash) entries, newest first
+    let data = slot_hashes.try_borrow_data()?;
+    let mut count = [0u8; 8];
+    count.copy_from_slice(data.get(..8).ok_or(BettingPoolError::JuryDrawTooEarly)?);
+    let mut seed_hash = None;
+    for entry in data[8..].chunks_exact(40).take(u64::from_le_bytes(count) as usize) {
+        let mut slot = [0u8; 8];
+        slot.copy_from_slice(&entry[..8]);
+        if u64::from_le_bytes(slot) < seed_slot {
+            break;
+        }
+        seed_hash = Some(&entry[8..]);
+    }
+    let seed_hash = seed_hash.ok_or(BettingPoolError::JuryDrawTooEarly)?;
+
+    Ok(hashv(&[pool.as_ref(), seed_hash]).to_bytes())
+}
@@ -1,83 +1,518 @@
+// The IDL instructions generated by `#[program]` still call `AccountInfo::realloc`.
+#![allow(deprecated)]
+// `initialize_pool` takes many arguments, and so does its generated CPI helper.
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Burn, MintTo},
-    token_interface::{Mint, TokenAccount, Token2022},
+    token_interface::{
+        self,
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_2022::{
+            extension::{BaseStateWithExtensions, StateWithExtensions},
+            state::Mint as MintState,
+        },
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        Burn, CloseAccount, Mint, MintTo, Token2022, TokenAccount, TokenInterface,
+        TokenMetadataInitialize, TokenMetadataUpdateField, TransferChecked, TransferHookUpdate,
+    },
+};
+
+pub mod events;
+pub mod jury;
+pub mod math;
+pub mod migration;
+pub mod oracle;
+pub mod position;
+
+use anchor_lang::solana_program::{
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
 };
+use events::*;
+use jury::{
+    jury_seed, vote_commitment, JuryPanel, JuryRegistry, JurorEntry, JURY_DRAW_TIMEOUT_SECONDS, MAX_JURORS,
+    MAX_JURY_SIZE,
+};
+use math::{bps_of, isqrt_ceil, mul_div, mul_div_u64, to_u64, Rounding};
+use migration::LegacyBettingPool;
+use oracle::{OraclePrice, SWITCHBOARD_DECIMALS};
+use position::Position;
 
 declare_id!("6y2JmXvbBisg2pS4p388BjiGeP1xWoEE1xgJjrENGYMq");
 
+/// Layout version of new and migrated pools. New fields go in the reserved bytes;
+/// a change that moves existing ones bumps this and teaches `migrate_pool` the
+/// old layout.
+pub const POOL_VERSION: u8 = 1;
+/// Maximum number of outcomes a single pool can offer
+pub const MAX_OUTCOMES: usize = 8;
+/// Maximum length in bytes of an outcome label such as "YES"
+pub const MAX_OUTCOME_LABEL_LEN: usize = 16;
+/// Maximum length in bytes of a pool name
+pub const MAX_POOL_NAME_LEN: usize = 32;
+/// Maximum length in bytes of a pool description
+pub const MAX_POOL_DESCRIPTION_LEN: usize = 256;
+/// Maximum length in bytes of the metadata URI of an outcome mint
+pub const MAX_OUTCOME_URI_LEN: usize = 200;
+/// Program that holds outcome token transfers of opted-in pools while a dispute
+/// is open or the answer is pending
+pub const OUTCOME_TRANSFER_HOOK_ID: Pubkey = pubkey!("2DUe9T8agLhgJb6WSsoqSxx5JQaMgc9gfY3s8871NEwK");
+/// Outcome index of the LONG side of a scalar market
+pub const LONG_OUTCOME: u8 = 0;
+/// Outcome index of the SHORT side of a scalar market
+pub const SHORT_OUTCOME: u8 = 1;
+/// Proposed in place of a winning outcome when a categorical market is ambiguous or
+/// cancelled. Every outcome is then refunded its share of the collateral.
+pub const INVALID_OUTCOME: u8 = u8::MAX;
+/// Dispute rounds a pool can go through; resolving the last one finalizes the pool
+pub const MAX_DISPUTE_ROUNDS: u8 = 3;
+/// Each dispute round multiplies the threshold and bond of the round before
+pub const DISPUTE_ESCALATION_FACTOR: u64 = 2;
+/// Denominator for probabilities and fees expressed in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Cap on the protocol and creator fees of a pool combined, in basis points
+pub const MAX_FEE_BPS: u64 = 1_000;
+/// Delay between a guardian asking to unpause and the unpause taking effect, until
+/// the admin sets another
+pub const DEFAULT_UNPAUSE_DELAY_SECONDS: i64 = 24 * 60 * 60;
+/// Maximum number of collateral mints the global config can allow
+pub const MAX_COLLATERAL_MINTS: usize = 16;
+/// Time after the dispute period ends from which a pool can be closed with claims
+/// still outstanding. Their collateral is swept to the treasury.
+pub const POOL_CLOSE_GRACE_PERIOD_SECONDS: i64 = 90 * 24 * 60 * 60;
+/// Latest an oracle price can be published after betting ends and still resolve
+/// the pool, so the caller cannot pick a price from long after the close
+pub const MAX_ORACLE_DELAY_SECONDS: i64 = 5 * 60;
+/// Time after betting ends from which the authority of an oracle pool nobody has
+/// resolved can propose the answer like in a manual pool
+pub const ORACLE_RESOLUTION_TIMEOUT_SECONDS: i64 = 7 * 24 * 60 * 60;
+
 #[program]
 pub mod project_5_capstone {
     use super::*;
 
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        protocol_fee_bps: u64,
+        min_dispute_period_seconds: i64,
+        max_dispute_period_seconds: i64,
+    ) -> Result<()> {
+        require!(protocol_fee_bps <= MAX_FEE_BPS, BettingPoolError::FeeTooHigh);
+        require!(
+            0 < min_dispute_period_seconds && min_dispute_period_seconds <= max_dispute_period_seconds,
+            BettingPoolError::InvalidDisputePeriod
+        );
+        
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.treasury = treasury;
+        config.protocol_fee_bps = protocol_fee_bps;
+        config.min_dispute_period_seconds = min_dispute_period_seconds;
+        config.max_dispute_period_seconds = max_dispute_period_seconds;
+        config.allowed_collateral_mints = Vec::new();
+        config.pool_creation_paused = false;
+        config.guardian = ctx.accounts.admin.key();
+        config.unpause_delay_seconds = DEFAULT_UNPAUSE_DELAY_SECONDS;
+        config.pause = PauseState::default();
+        config.bump = ctx.bumps.config;
+        
+        Ok(())
+    }
+
+    pub fn update_protocol_fee(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        protocol_fee_bps: u64,
+    ) -> Result<()> {
+        require!(protocol_fee_bps <= MAX_FEE_BPS, BettingPoolError::FeeTooHigh);
+        
+        // Existing pools keep the fee they were created with
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.protocol_fee_bps = protocol_fee_bps;
+        
+        Ok(())
+    }
+
+    pub fn update_dispute_period_bounds(
+        ctx: Context<UpdateConfig>,
+        min_dispute_period_seconds: i64,
+        max_dispute_period_seconds: i64,
+    ) -> Result<()> {
+        require!(
+            0 < min_dispute_period_seconds && min_dispute_period_seconds <= max_dispute_period_seconds,
+            BettingPoolError::InvalidDisputePeriod
+        );
+        
+        let config = &mut ctx.accounts.config;
+        config.min_dispute_period_seconds = min_dispute_period_seconds;
+        config.max_dispute_period_seconds = max_dispute_period_seconds;
+        
+        Ok(())
+    }
+
+    pub fn set_collateral_mint_allowed(
+        ctx: Context<UpdateConfig>,
+        collateral_mint: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        let mints = &mut ctx.accounts.config.allowed_collateral_mints;
+        mints.retain(|mint| *mint != collateral_mint);
+        
+        if allowed {
+            require!(mints.len() < MAX_COLLATERAL_MINTS, BettingPoolError::TooManyCollateralMints);
+            mints.push(collateral_mint);
+        }
+        
+        Ok(())
+    }
+
+    pub fn set_pool_creation_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.pool_creation_paused = paused;
+        
+        Ok(())
+    }
+
+    pub fn set_guardian(
+        ctx: Context<UpdateConfig>,
+        guardian: Pubkey,
+        unpause_delay_seconds: i64,
+    ) -> Result<()> {
+        require!(unpause_delay_seconds >= 0, BettingPoolError::InvalidUnpauseDelay);
+        
+        let config = &mut ctx.accounts.config;
+        config.guardian = guardian;
+        config.unpause_delay_seconds = unpause_delay_seconds;
+        
+        Ok(())
+    }
+
+    /// Pause the given pool, or every pool when none is given
+    pub fn pause(ctx: Context<SetPause>) -> Result<()> {
+        let pause = match ctx.accounts.pool.as_mut() {
+            Some(pool) => &mut pool.pause,
+            None => &mut ctx.accounts.config.pause,
+        };
+        pause.pause();
+        
+        Ok(())
+    }
+
+    pub fn request_unpause(ctx: Context<SetPause>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let delay = ctx.accounts.config.unpause_delay_seconds;
+        let pause = match ctx.accounts.pool.as_mut() {
+            Some(pool) => &mut pool.pause,
+            None => &mut ctx.accounts.config.pause,
+        };
+        pause.request_unpause(current_time, delay)
+    }
+
+    pub fn unpause(ctx: Context<SetPause>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let pause = match ctx.accounts.pool.as_mut() {
+            Some(pool) => &mut pool.pause,
+            None => &mut ctx.accounts.config.pause,
+        };
+        pause.unpause(current_time)
+    }
+
     pub fn initialize_pool(
         ctx: Context<InitializePool>,
+        market_id: u64,
+        market_kind: MarketKind,
+        resolution_source: ResolutionSource,
+        outcome_count: u8,
         dispute_period_seconds: i64,
         dispute_threshold: u64,
         pool_name: String,
         pool_description: String,
         end_time: i64,
     ) -> Result<()> {
+        require!(
+            (2..=MAX_OUTCOMES).contains(&(outcome_count as usize)),
+            BettingPoolError::InvalidOutcomeCount
+        );
+        if let MarketKind::Scalar { lower_bound, upper_bound } = market_kind {
+            require!(outcome_count == 2, BettingPoolError::InvalidOutcomeCount);
+            require!(lower_bound < upper_bound, BettingPoolError::InvalidScalarBounds);
+        }
+
+        let config = &ctx.accounts.config;
+        require!(!config.pool_creation_paused, BettingPoolError::PoolCreationPaused);
+        require!(
+            config.is_collateral_mint_allowed(&ctx.accounts.collateral_mint.key()),
+            BettingPoolError::CollateralMintNotAllowed
+        );
+        require!(
+            (config.min_dispute_period_seconds..=config.max_dispute_period_seconds)
+                .contains(&dispute_period_seconds),
+            BettingPoolError::InvalidDisputePeriod
+        );
+        require!(end_time > Clock::get()?.unix_timestamp, BettingPoolError::EndTimeInPast);
+        require!(pool_name.len() <= MAX_POOL_NAME_LEN, BettingPoolError::PoolNameTooLong);
+        require!(
+            pool_description.len() <= MAX_POOL_DESCRIPTION_LEN,
+            BettingPoolError::PoolDescriptionTooLong
+        );
+
+        if let ResolutionSource::Oracle { provider, .. } = resolution_source {
+            // An oracle price only answers a yes/no question about the strike
+            if market_kind == MarketKind::Categorical {
+                require!(outcome_count == 2, BettingPoolError::InvalidOutcomeCount);
+            }
+            if let OracleProvider::Switchboard { decimals } = provider {
+                require!(decimals <= SWITCHBOARD_DECIMALS, BettingPoolError::InvalidOracleConfig);
+            }
+        }
+
         let pool = &mut ctx.accounts.pool;
         let bump = ctx.bumps.pool;
 
         // Initialize pool data
         pool.authority = ctx.accounts.authority.key();
-        pool.yes_mint = ctx.accounts.yes_mint.key();
-        pool.no_mint = ctx.accounts.no_mint.key();
-        pool.total_yes_tokens = 0;
-        pool.total_no_tokens = 0;
+        pool.operator = ctx.accounts.authority.key();
+        pool.pending_operator = None;
+        pool.market_id = market_id;
+        pool.collateral_mint = ctx.accounts.collateral_mint.key();
+        pool.collateral_vault = ctx.accounts.collateral_vault.key();
+        pool.total_collateral = 0;
+        pool.complete_sets = 0;
+        pool.protocol_fee_bps = ctx.accounts.config.protocol_fee_bps;
+        pool.creator_fee_bps = 0;
+        pool.protocol_fees = 0;
+        pool.creator_fees = 0;
+        pool.market_kind = market_kind;
+        pool.resolution_source = resolution_source;
+        pool.outcome_count = outcome_count;
+        pool.outcomes = Vec::with_capacity(outcome_count as usize);
         pool.solution_proposed = false;
         pool.solution_winner = None;
+        pool.solution_value = None;
         pool.dispute_period_seconds = dispute_period_seconds;
         pool.dispute_threshold = dispute_threshold;
+        pool.proposer_bond = 0;
+        pool.dispute_bond = 0;
+        pool.jury_resolution = false;
+        pool.transfer_hook = false;
+        pool.dispute_round = 0;
         pool.is_disputed = false;
         pool.is_finalized = false;
+        pool.pause = PauseState::default();
         pool.bump = bump;
         pool.name = pool_name;
         pool.description = pool_description;
         pool.end_time = end_time;
         pool.created_at = Clock::get()?.unix_timestamp;
+        pool.amm = None;
+        pool.version = POOL_VERSION;
+        pool.disputed_at = 0;
+        pool.dispute_slot = 0;
+        pool.reserved = [0; BettingPool::RESERVED_SPACE];
+
+        emit!(PoolCreated {
+            pool: pool.key(),
+            authority: pool.authority,
+            market_id,
+            collateral_mint: pool.collateral_mint,
+            outcome_count,
+            end_time,
+            timestamp: pool.created_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_outcome<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeOutcome<'info>>,
+        outcome_index: u8,
+        label: String,
+        uri: String,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        // Outcomes are added in order until the table is full
+        require!(
+            pool.outcomes.len() < pool.outcome_count as usize,
+            BettingPoolError::OutcomesAlreadyInitialized
+        );
+        require!(
+            outcome_index as usize == pool.outcomes.len(),
+            BettingPoolError::InvalidOutcome
+        );
+        require!(label.len() <= MAX_OUTCOME_LABEL_LEN, BettingPoolError::OutcomeLabelTooLong);
+        require!(uri.len() <= MAX_OUTCOME_URI_LEN, BettingPoolError::OutcomeUriTooLong);
+        
+        pool.outcomes.push(Outcome {
+            mint: ctx.accounts.outcome_mint.key(),
+            label: label.clone(),
+            total_tokens: 0,
+            payout_pool: 0,
+        });
+        
+        // Name the mint after the pool so wallets can tell the outcomes apart
+        let pool = &ctx.accounts.pool;
+        let metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(pool.key()))?,
+            mint: ctx.accounts.outcome_mint.key(),
+            name: format!("{} {}", pool.name, label),
+            symbol: label,
+            uri,
+            additional_metadata: Vec::new(),
+        };
+        fund_metadata_rent(
+            &ctx.accounts.outcome_mint.to_account_info(),
+            &metadata,
+            ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TokenMetadataInitialize {
+            program_id: ctx.accounts.token_program.to_account_info(),
+            metadata: ctx.accounts.outcome_mint.to_account_info(),
+            update_authority: ctx.accounts.pool.to_account_info(),
+            mint_authority: ctx.accounts.pool.to_account_info(),
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::token_metadata_initialize(cpi_ctx, metadata.name, metadata.symbol, metadata.uri)?;
+        
+        // Point the mint at the hook program and register the pool as the extra
+        // account it reads on every transfer
+        if pool.transfer_hook {
+            let [extra_account_metas, hook_program] = ctx.remaining_accounts else {
+                return err!(BettingPoolError::MissingTransferHookAccounts);
+            };
+            require_keys_eq!(
+                hook_program.key(),
+                OUTCOME_TRANSFER_HOOK_ID,
+                BettingPoolError::MissingTransferHookAccounts
+            );
+            
+            let cpi_accounts = TransferHookUpdate {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::transfer_hook_update(cpi_ctx, Some(OUTCOME_TRANSFER_HOOK_ID))?;
+            
+            initialize_transfer_hook_metas(
+                pool,
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.outcome_mint.to_account_info(),
+                extra_account_metas.clone(),
+                hook_program.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+        
+        Ok(())
+    }
+
+    pub fn update_outcome_uri(ctx: Context<UpdateOutcomeUri>, _outcome: u8, uri: String) -> Result<()> {
+        require!(uri.len() <= MAX_OUTCOME_URI_LEN, BettingPoolError::OutcomeUriTooLong);
+        
+        // A longer URI grows the mint, so the operator tops up its rent first
+        let outcome_mint = ctx.accounts.outcome_mint.to_account_info();
+        let metadata = {
+            let data = outcome_mint.try_borrow_data()?;
+            let mut metadata = StateWithExtensions::<MintState>::unpack(&data)?
+                .get_variable_len_extension::<TokenMetadata>()?;
+            metadata.uri = uri.clone();
+            metadata
+        };
+        fund_metadata_rent(
+            &outcome_mint,
+            &metadata,
+            ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TokenMetadataUpdateField {
+            program_id: ctx.accounts.token_program.to_account_info(),
+            metadata: outcome_mint,
+            update_authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::token_metadata_update_field(cpi_ctx, Field::Uri, uri)
+    }
 
+    pub fn enable_transfer_hook(ctx: Context<EnableTransferHook>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        // Mints created before this would let their tokens move freely
+        require!(pool.outcomes.is_empty(), BettingPoolError::TransferHookLocked);
+        
+        pool.transfer_hook = true;
+        
         Ok(())
     }
 
     pub fn mint_prediction_tokens(
         ctx: Context<MintPredictionTokens>,
         amount: u64,
-        prediction: bool, // true for YES, false for NO
+        outcome: u8,
     ) -> Result<()> {
         // Check if predictions are still allowed
         let current_time = Clock::get()?.unix_timestamp;
         require!(current_time < ctx.accounts.pool.end_time, BettingPoolError::BettingPeriodEnded);
+        require!(ctx.accounts.pool.outcomes_ready(), BettingPoolError::OutcomesNotInitialized);
+        require!(ctx.accounts.pool.amm.is_none(), BettingPoolError::AmmActive);
+        
+        // Deposit collateral into the pool vault, 1 collateral unit per prediction token
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.collateral_mint.decimals)?;
         
         // Create pool seeds for signing
         let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
         let pool_seeds = &[
             b"pool".as_ref(),
             pool.authority.as_ref(),
+            market_id.as_ref(),
             &[pool.bump],
         ];
         let signer = &[&pool_seeds[..]];
         
-        // Get mint and token account based on prediction
-        let mint = if prediction {
-            ctx.accounts.yes_mint.to_account_info()
-        } else {
-            ctx.accounts.no_mint.to_account_info()
-        };
-        
-        let token_account = if prediction {
-            ctx.accounts.user_yes_token.to_account_info()
-        } else {
-            ctx.accounts.user_no_token.to_account_info()
-        };
-        
         // Create CPI context for minting
         let cpi_accounts = MintTo {
-            mint,
-            to: token_account,
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            to: ctx.accounts.user_outcome_token.to_account_info(),
             authority: ctx.accounts.pool.to_account_info(),
         };
         
@@ -85,15 +520,32 @@ pub mod project_5_capstone {
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         
         // Mint the tokens
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::mint_to(cpi_ctx, amount)?;
         
         // Update pool token counters
         let pool = &mut ctx.accounts.pool;
-        if prediction {
-            pool.total_yes_tokens = pool.total_yes_tokens.checked_add(amount).unwrap();
-        } else {
-            pool.total_no_tokens = pool.total_no_tokens.checked_add(amount).unwrap();
-        }
+        pool.total_collateral = pool
+            .total_collateral
+            .checked_add(amount)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let outcome_state = pool.outcome_mut(outcome)?;
+        outcome_state.total_tokens = outcome_state
+            .total_tokens
+            .checked_add(amount)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        let position = &mut ctx.accounts.position;
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        position.record_deposit(amount)?;
+        position.record_mint(outcome, amount)?;
+        
+        emit!(TokensMinted {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            outcome,
+            amount,
+            timestamp: current_time,
+        });
         
         Ok(())
     }
@@ -101,29 +553,17 @@ pub mod project_5_capstone {
     pub fn burn_prediction_tokens(
         ctx: Context<BurnPredictionTokens>,
         amount: u64,
-        prediction: bool, // true for YES, false for NO
+        outcome: u8,
     ) -> Result<()> {
         // Check if predictions are still allowed
         let current_time = Clock::get()?.unix_timestamp;
         require!(current_time < ctx.accounts.pool.end_time, BettingPoolError::BettingPeriodEnded);
-        
-        // Get mint and token account based on prediction
-        let mint = if prediction {
-            ctx.accounts.yes_mint.to_account_info()
-        } else {
-            ctx.accounts.no_mint.to_account_info()
-        };
-        
-        let token_account = if prediction {
-            ctx.accounts.user_yes_token.to_account_info()
-        } else {
-            ctx.accounts.user_no_token.to_account_info()
-        };
+        require!(ctx.accounts.pool.amm.is_none(), BettingPoolError::AmmActive);
         
         // Create CPI context for burning
         let cpi_accounts = Burn {
-            mint,
-            from: token_account,
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            from: ctx.accounts.user_outcome_token.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
         
@@ -131,450 +571,3476 @@ pub mod project_5_capstone {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         
         // Burn the tokens
-        token::burn(cpi_ctx, amount)?;
+        token_interface::burn(cpi_ctx, amount)?;
         
-        // Update pool token counters
-        let pool = &mut ctx.accounts.pool;
-        if prediction {
-            pool.total_yes_tokens = pool.total_yes_tokens.checked_sub(amount).unwrap();
-        } else {
-            pool.total_no_tokens = pool.total_no_tokens.checked_sub(amount).unwrap();
-        }
+        // Return the collateral backing the burned tokens
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
         
-        Ok(())
-    }
-
-    pub fn propose_solution(
-        ctx: Context<ProposeSolution>,
-        winner: bool, // true for YES, false for NO
-    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.user_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.collateral_mint.decimals)?;
+        
+        // Update pool token counters
         let pool = &mut ctx.accounts.pool;
-        require!(!pool.solution_proposed, BettingPoolError::SolutionAlreadyProposed);
+        pool.total_collateral = pool
+            .total_collateral
+            .checked_sub(amount)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let outcome_state = pool.outcome_mut(outcome)?;
+        outcome_state.total_tokens = outcome_state
+            .total_tokens
+            .checked_sub(amount)
+            .ok_or(BettingPoolError::MathOverflow)?;
         
-        // Check if proposing solution is allowed (only after end_time)
-        let current_time = Clock::get()?.unix_timestamp;
-        require!(current_time >= pool.end_time, BettingPoolError::BettingPeriodNotEnded);
+        // Only the caller's own stake can be refunded, never tokens received by transfer
+        let position = &mut ctx.accounts.position;
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        require!(amount <= position.single_sided(outcome)?, BettingPoolError::InsufficientCollateral);
+        position.record_withdrawal(amount)?;
+        position.record_burn(outcome, amount)?;
         
-        pool.solution_proposed = true;
-        pool.solution_winner = Some(winner);
-        pool.dispute_period_start = Clock::get()?.unix_timestamp;
-        pool.dispute_period_end = pool.dispute_period_start + pool.dispute_period_seconds;
+        emit!(TokensBurned {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            outcome,
+            amount,
+            timestamp: current_time,
+        });
         
         Ok(())
     }
 
-    pub fn dispute_solution(
-        ctx: Context<DisputeSolution>
+    pub fn mint_complete_set<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintCompleteSet<'info>>,
+        amount: u64,
     ) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < ctx.accounts.pool.end_time, BettingPoolError::BettingPeriodEnded);
+        require!(ctx.accounts.pool.outcomes_ready(), BettingPoolError::OutcomesNotInitialized);
+        require!(amount > 0, BettingPoolError::InvalidAmount);
         
-        require!(pool.solution_proposed, BettingPoolError::NoSolutionProposed);
-        require!(!pool.is_disputed, BettingPoolError::AlreadyDisputed);
-        require!(clock.unix_timestamp <= pool.dispute_period_end, BettingPoolError::DisputePeriodEnded);
+        // A set is a bet on every outcome at once, so in a parimutuel pool it would
+        // draw on the bettors' pot. Sets only exist where nobody bets that way.
+        require!(ctx.accounts.pool.amm.is_some(), BettingPoolError::CompleteSetsRequireAmm);
         
-        // Check if disputer has enough tokens of the losing side
-        let winner = pool.solution_winner.unwrap();
-        let disputer_tokens = if winner {
-            // If YES won, then NO is disputing
-            ctx.accounts.user_no_token.amount
-        } else {
-            // If NO won, then YES is disputing
-            ctx.accounts.user_yes_token.amount
+        let outcome_accounts = outcome_token_accounts(
+            &ctx.accounts.pool,
+            &ctx.accounts.user.key(),
+            ctx.remaining_accounts,
+        )?;
+        
+        // Deposit 1 collateral unit per set
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
         };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.collateral_mint.decimals)?;
+        
+        // Mint one token of every outcome per set
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
         
-        require!(disputer_tokens >= pool.dispute_threshold, BettingPoolError::InsufficientTokensForDispute);
+        for (outcome_mint, user_outcome_token) in outcome_accounts {
+            let cpi_accounts = MintTo {
+                mint: outcome_mint,
+                to: user_outcome_token,
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::mint_to(cpi_ctx, amount)?;
+        }
         
-        pool.is_disputed = true;
-        pool.disputer = Some(ctx.accounts.user.key());
+        ctx.accounts.pool.add_complete_sets(amount)?;
+        
+        let position = &mut ctx.accounts.position;
+        position.open(ctx.accounts.pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        position.record_deposit(amount)?;
+        for outcome in 0..ctx.accounts.pool.outcome_count {
+            position.record_mint(outcome, amount)?;
+        }
+        
+        emit!(CompleteSetsMinted {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            timestamp: current_time,
+        });
         
         Ok(())
     }
 
-    pub fn resolve_dispute(
-        ctx: Context<ResolveDispute>,
-        new_winner: bool, // true for YES, false for NO
+    pub fn redeem_complete_set<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemCompleteSet<'info>>,
+        amount: u64,
     ) -> Result<()> {
+        // A full set is worth 1 collateral unit whatever the outcome, so it can be
+        // redeemed at any time, including after the pool is finalized
+        require!(amount > 0, BettingPoolError::InvalidAmount);
+        
+        let outcome_accounts = outcome_token_accounts(
+            &ctx.accounts.pool,
+            &ctx.accounts.user.key(),
+            ctx.remaining_accounts,
+        )?;
+        
+        // Burn one token of every outcome per set
+        for (outcome_mint, user_outcome_token) in outcome_accounts {
+            let cpi_accounts = Burn {
+                mint: outcome_mint,
+                from: user_outcome_token,
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::burn(cpi_ctx, amount)?;
+        }
+        
+        // Once payouts are settled, each outcome pays its share of the sets
         let pool = &mut ctx.accounts.pool;
+        let payout = if pool.is_finalized {
+            let mut payout: u64 = 0;
+            for outcome in 0..pool.outcome_count {
+                let share = pool.take_payout(outcome, amount)?;
+                payout = payout.checked_add(share).ok_or(BettingPoolError::MathOverflow)?;
+            }
+            payout
+        } else {
+            pool.remove_complete_sets(amount)?;
+            amount
+        };
         
-        require!(pool.is_disputed, BettingPoolError::NotDisputed);
-        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
+        // Return the collateral backing the burned sets
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
         
-        // Only the authority can resolve disputes
-        require!(ctx.accounts.authority.key() == pool.authority, BettingPoolError::Unauthorized);
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.user_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, payout, ctx.accounts.collateral_mint.decimals)?;
         
-        // Set the new winner
-        pool.solution_winner = Some(new_winner);
-        pool.is_disputed = false;
+        let position = &mut ctx.accounts.position;
+        position.open(ctx.accounts.pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        position.record_withdrawal(payout)?;
+        for outcome in 0..ctx.accounts.pool.outcome_count {
+            position.record_burn(outcome, amount)?;
+        }
         
-        // Reset dispute period to allow for another round of disputes
-        pool.dispute_period_start = Clock::get()?.unix_timestamp;
-        pool.dispute_period_end = pool.dispute_period_start + pool.dispute_period_seconds;
+        emit!(CompleteSetsRedeemed {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            payout,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         
         Ok(())
     }
 
-    pub fn finalize_pool(ctx: Context<FinalizePool>) -> Result<()> {
+    pub fn fund_amm(ctx: Context<FundAmm>, amount: u64) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < pool.end_time, BettingPoolError::BettingPeriodEnded);
+        require!(pool.outcomes_ready(), BettingPoolError::OutcomesNotInitialized);
+        require!(pool.outcome_count == 2, BettingPoolError::AmmRequiresBinaryMarket);
+        require!(pool.amm.is_none(), BettingPoolError::AmmAlreadyFunded);
+        require!(pool.total_collateral == 0, BettingPoolError::PoolHasBets);
+        require!(amount > 0, BettingPoolError::InvalidAmount);
         
-        require!(pool.solution_proposed, BettingPoolError::NoSolutionProposed);
-        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
-        require!(clock.unix_timestamp > pool.dispute_period_end, BettingPoolError::DisputePeriodNotEnded);
-        require!(!pool.is_disputed, BettingPoolError::PoolIsDisputed);
+        // The liquidity mints `amount` complete sets that the pool keeps as reserves
+        pool.add_complete_sets(amount)?;
+        pool.amm = Some(AmmState::new(amount));
         
-        // Set the pool as finalized
-        pool.is_finalized = true;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.authority_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.collateral_mint.decimals)?;
+        
+        emit!(AmmFunded {
+            pool: ctx.accounts.pool.key(),
+            authority: ctx.accounts.authority.key(),
+            liquidity: amount,
+            timestamp: current_time,
+        });
         
         Ok(())
     }
 
-    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+    pub fn buy_outcome_tokens(
+        ctx: Context<BuyOutcomeTokens>,
+        outcome: u8,
+        amount: u64,
+        max_cost: u64,
+    ) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < pool.end_time, BettingPoolError::BettingPeriodEnded);
+        require!(amount > 0, BettingPoolError::InvalidAmount);
         
-        require!(pool.is_finalized, BettingPoolError::PoolNotFinalized);
+        let cost = pool
+            .amm
+            .as_mut()
+            .ok_or(BettingPoolError::AmmNotFunded)?
+            .buy(outcome, amount)?;
+        require!(cost <= max_cost, BettingPoolError::SlippageExceeded);
+        pool.add_complete_sets(cost)?;
         
-        let winner = pool.solution_winner.unwrap();
+        let position = &mut ctx.accounts.position;
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        position.record_deposit(cost)?;
+        position.record_mint(outcome, amount)?;
         
-        // Check if user holds winning tokens
-        let winning_token_account = if winner {
-            &ctx.accounts.user_yes_token
-        } else {
-            &ctx.accounts.user_no_token
+        // Take the cost from the user
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
         };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, cost, ctx.accounts.collateral_mint.decimals)?;
         
-        require!(winning_token_account.amount > 0, BettingPoolError::NoWinningTokens);
+        // Hand the bought tokens out of the reserve
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
         
-        // Calculate proportion of winnings
-        let total_winning_tokens = if winner {
-            pool.total_yes_tokens
-        } else {
-            pool.total_no_tokens
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            to: ctx.accounts.user_outcome_token.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
         };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::mint_to(cpi_ctx, amount)?;
         
-        let user_winning_proportion = winning_token_account.amount as f64 / total_winning_tokens as f64;
+        emit!(OutcomeTokensBought {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            outcome,
+            amount,
+            cost,
+            timestamp: current_time,
+        });
         
-        // This is where you would implement token distribution logic
-        // For example, transferring a share of the pool funds to the winner
+        Ok(())
+    }
+
+    pub fn sell_outcome_tokens(
+        ctx: Context<SellOutcomeTokens>,
+        outcome: u8,
+        amount: u64,
+        min_proceeds: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < pool.end_time, BettingPoolError::BettingPeriodEnded);
+        require!(amount > 0, BettingPoolError::InvalidAmount);
         
-        // For now, we'll just burn the winning tokens
-        let mint = if winner {
-            ctx.accounts.yes_mint.to_account_info()
-        } else {
-            ctx.accounts.no_mint.to_account_info()
-        };
+        let proceeds = pool
+            .amm
+            .as_mut()
+            .ok_or(BettingPoolError::AmmNotFunded)?
+            .sell(outcome, amount)?;
+        require!(proceeds >= min_proceeds, BettingPoolError::SlippageExceeded);
+        pool.remove_complete_sets(proceeds)?;
         
-        let token_account = if winner {
-            ctx.accounts.user_yes_token.to_account_info()
-        } else {
-            ctx.accounts.user_no_token.to_account_info()
-        };
+        let position = &mut ctx.accounts.position;
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        position.record_withdrawal(proceeds)?;
+        position.record_burn(outcome, amount)?;
         
+        // The sold tokens go back into the reserve, which is virtual, so burn them
         let cpi_accounts = Burn {
-            mint,
-            from: token_account,
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            from: ctx.accounts.user_outcome_token.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
-        
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)?;
         
-        token::burn(cpi_ctx, winning_token_account.amount)?;
-        
-        msg!("User claimed winnings: {:.2}% of the pool", user_winning_proportion * 100.0);
-        
-        Ok(())
+        // Pay the user for the complete sets redeemed out of the reserves
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.user_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, proceeds, ctx.accounts.collateral_mint.decimals)?;
+        
+        emit!(OutcomeTokensSold {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            outcome,
+            amount,
+            proceeds,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_operator: Pubkey) -> Result<()> {
+        // Proposing again replaces the pending operator, proposing yourself cancels it
+        let pool = &mut ctx.accounts.pool;
+        pool.pending_operator = (new_operator != pool.operator).then_some(new_operator);
+        
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let previous_operator = pool.operator;
+        pool.operator = ctx.accounts.new_operator.key();
+        pool.pending_operator = None;
+        
+        emit!(AuthorityTransferred {
+            pool: pool.key(),
+            previous_operator,
+            new_operator: pool.operator,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn configure_creator_fee(ctx: Context<ConfigureCreatorFee>, creator_fee_bps: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        // Bettors see the fee before they commit any collateral
+        require!(pool.total_collateral == 0, BettingPoolError::FeesLocked);
+        require!(
+            pool.protocol_fee_bps
+                .checked_add(creator_fee_bps)
+                .ok_or(BettingPoolError::MathOverflow)?
+                <= MAX_FEE_BPS,
+            BettingPoolError::FeeTooHigh
+        );
+        
+        pool.creator_fee_bps = creator_fee_bps;
+        
+        Ok(())
+    }
+
+    pub fn configure_bonds(
+        ctx: Context<ConfigureBonds>,
+        proposer_bond: u64,
+        dispute_bond: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(!pool.solution_proposed, BettingPoolError::SolutionAlreadyProposed);
+        require!(
+            pool.proposer_bond == 0 && pool.dispute_bond == 0,
+            BettingPoolError::BondsAlreadyConfigured
+        );
+        
+        // The authority backs its own proposals up front; disputers post theirs later
+        pool.proposer_bond = proposer_bond;
+        pool.dispute_bond = dispute_bond;
+        
+        if proposer_bond > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.authority_collateral.to_account_info(),
+                mint: ctx.accounts.collateral_mint.to_account_info(),
+                to: ctx.accounts.bond_escrow.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, proposer_bond, ctx.accounts.collateral_mint.decimals)?;
+        }
+        
+        Ok(())
+    }
+
+    pub fn propose_solution(
+        ctx: Context<ProposeSolution>,
+        winner: u8,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(!pool.solution_proposed, BettingPoolError::SolutionAlreadyProposed);
+        require!(pool.market_kind == MarketKind::Categorical, BettingPoolError::InvalidMarketKind);
+        require!(pool.accepts_manual_proposal(current_time)?, BettingPoolError::ManualResolutionDisabled);
+        require!(pool.is_valid_answer(winner), BettingPoolError::InvalidOutcome);
+        
+        // Check if proposing solution is allowed (only after end_time)
+        require!(current_time >= pool.end_time, BettingPoolError::BettingPeriodNotEnded);
+        
+        pool.solution_proposed = true;
+        pool.solution_winner = Some(winner);
+        pool.dispute_period_start = Clock::get()?.unix_timestamp;
+        pool.dispute_period_end = pool
+            .dispute_period_start
+            .checked_add(pool.dispute_period_seconds)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        emit!(SolutionProposed {
+            pool: pool.key(),
+            proposer: ctx.accounts.authority.key(),
+            winner: pool.solution_winner,
+            value: None,
+            dispute_period_end: pool.dispute_period_end,
+            timestamp: pool.dispute_period_start,
+        });
+        
+        Ok(())
+    }
+
+    pub fn propose_scalar_solution(
+        ctx: Context<ProposeScalarSolution>,
+        value: i64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(!pool.solution_proposed, BettingPoolError::SolutionAlreadyProposed);
+        require!(pool.market_kind != MarketKind::Categorical, BettingPoolError::InvalidMarketKind);
+        require!(pool.accepts_manual_proposal(current_time)?, BettingPoolError::ManualResolutionDisabled);
+        
+        // Check if proposing solution is allowed (only after end_time)
+        require!(current_time >= pool.end_time, BettingPoolError::BettingPeriodNotEnded);
+        
+        pool.solution_proposed = true;
+        pool.solution_value = Some(value);
+        pool.dispute_period_start = current_time;
+        pool.dispute_period_end = pool
+            .dispute_period_start
+            .checked_add(pool.dispute_period_seconds)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        emit!(SolutionProposed {
+            pool: pool.key(),
+            proposer: ctx.accounts.authority.key(),
+            winner: None,
+            value: pool.solution_value,
+            dispute_period_end: pool.dispute_period_end,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn resolve_from_oracle(ctx: Context<ResolveFromOracle>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(!pool.solution_proposed, BettingPoolError::SolutionAlreadyProposed);
+        require!(current_time >= pool.end_time, BettingPoolError::BettingPeriodNotEnded);
+        
+        let ResolutionSource::Oracle {
+            provider,
+            price_feed,
+            strike,
+            comparison,
+            max_staleness_seconds,
+            max_confidence_bps,
+        } = pool.resolution_source
+        else {
+            return err!(BettingPoolError::NotOracleResolved);
+        };
+        require_keys_eq!(ctx.accounts.price_feed.key(), price_feed, BettingPoolError::InvalidOracleAccount);
+        
+        let price = match provider {
+            OracleProvider::Pyth => OraclePrice::from_pyth(&ctx.accounts.price_feed)?,
+            OracleProvider::Switchboard { decimals } => {
+                OraclePrice::from_switchboard(&ctx.accounts.price_feed, decimals)?
+            }
+        };
+        
+        // The price must be published shortly after betting closed. Freshness is
+        // measured from the close too, so the pool can be resolved at any time later.
+        require!(price.publish_time >= pool.end_time, BettingPoolError::OraclePriceTooEarly);
+        let latest_publish_time = pool
+            .end_time
+            .checked_add(MAX_ORACLE_DELAY_SECONDS)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        require!(price.publish_time <= latest_publish_time, BettingPoolError::OraclePriceTooLate);
+        require!(
+            price.publish_time - pool.end_time <= max_staleness_seconds,
+            BettingPoolError::StaleOraclePrice
+        );
+        require!(
+            price.conf as u128 * BPS_DENOMINATOR as u128
+                <= max_confidence_bps as u128 * price.price.unsigned_abs() as u128,
+            BettingPoolError::OracleConfidenceTooWide
+        );
+        
+        match pool.market_kind {
+            MarketKind::Categorical => {
+                let winner = if comparison.holds(price.price, strike) { 0 } else { 1 };
+                pool.solution_winner = Some(winner);
+            }
+            MarketKind::Scalar { .. } => pool.solution_value = Some(price.price),
+        }
+        
+        // The oracle's answer is final, so there is no dispute window
+        pool.solution_proposed = true;
+        pool.dispute_period_start = current_time;
+        pool.dispute_period_end = current_time;
+        pool.settle_payouts()?;
+        pool.is_finalized = true;
+        
+        emit!(OracleResolved {
+            pool: pool.key(),
+            price: price.price,
+            conf: price.conf,
+            publish_time: price.publish_time,
+            timestamp: current_time,
+        });
+        emit_if_finalized(pool, current_time);
+        
+        Ok(())
+    }
+
+    pub fn dispute_solution(
+        ctx: Context<DisputeSolution>,
+        outcome: u8,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        
+        require!(pool.solution_proposed, BettingPoolError::NoSolutionProposed);
+        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
+        require!(!pool.is_disputed, BettingPoolError::AlreadyDisputed);
+        require!(clock.unix_timestamp <= pool.dispute_period_end, BettingPoolError::DisputePeriodEnded);
+        
+        // Only holders of a losing outcome can dispute. Any value hurts one side of a
+        // scalar market, so either side may dispute there.
+        if let Some(winner) = pool.solution_winner {
+            require!(outcome != winner, BettingPoolError::InvalidOutcome);
+        }
+        
+        let disputer_tokens = ctx.accounts.user_outcome_token.amount;
+        require!(
+            disputer_tokens >= pool.round_dispute_threshold()?,
+            BettingPoolError::InsufficientTokensForDispute
+        );
+        
+        pool.is_disputed = true;
+        pool.disputer = Some(ctx.accounts.user.key());
+        pool.dispute_slot = clock.slot;
+        pool.disputed_at = clock.unix_timestamp;
+        
+        // Escrow the disputer's bond until the dispute is resolved
+        let dispute_bond = pool.round_dispute_bond()?;
+        if dispute_bond > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_collateral.to_account_info(),
+                mint: ctx.accounts.collateral_mint.to_account_info(),
+                to: ctx.accounts.bond_escrow.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, dispute_bond, ctx.accounts.collateral_mint.decimals)?;
+        }
+        
+        emit!(SolutionDisputed {
+            pool: ctx.accounts.pool.key(),
+            disputer: ctx.accounts.user.key(),
+            outcome,
+            tokens: disputer_tokens,
+            bond: dispute_bond,
+            round: ctx.accounts.pool.dispute_round,
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        new_winner: u8,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        require!(pool.is_disputed, BettingPoolError::NotDisputed);
+        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
+        require!(pool.market_kind == MarketKind::Categorical, BettingPoolError::InvalidMarketKind);
+        require!(pool.is_valid_answer(new_winner), BettingPoolError::InvalidOutcome);
+        
+        // A jury dispute only comes back to the operator when no panel could be
+        // drawn for it, for instance because too few jurors are staked
+        let current_time = Clock::get()?.unix_timestamp;
+        if pool.jury_resolution {
+            let draw_timeout = pool
+                .disputed_at
+                .checked_add(JURY_DRAW_TIMEOUT_SECONDS)
+                .ok_or(BettingPoolError::MathOverflow)?;
+            require!(
+                current_time >= draw_timeout && ctx.accounts.panel.data_is_empty(),
+                BettingPoolError::JuryResolutionEnabled
+            );
+        }
+        
+        // Only the operator can resolve disputes
+        require!(ctx.accounts.authority.key() == pool.operator, BettingPoolError::Unauthorized);
+        
+        let upheld = pool.solution_winner == Some(new_winner);
+        
+        // Set the new winner
+        pool.solution_winner = Some(new_winner);
+        let amount = pool.settle_bonds(upheld)?;
+        pool.end_dispute_round(current_time)?;
+        emit_dispute_resolved(pool, upheld, current_time)?;
+        
+        let recipient = if upheld {
+            ctx.accounts.authority_collateral.to_account_info()
+        } else {
+            ctx.accounts.disputer_collateral.to_account_info()
+        };
+        pay_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.bond_escrow,
+            &ctx.accounts.collateral_mint,
+            recipient,
+            &ctx.accounts.collateral_token_program,
+            amount,
+        )
+    }
+
+    pub fn resolve_scalar_dispute(
+        ctx: Context<ResolveScalarDispute>,
+        new_value: i64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        require!(pool.is_disputed, BettingPoolError::NotDisputed);
+        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
+        require!(pool.market_kind != MarketKind::Categorical, BettingPoolError::InvalidMarketKind);
+        
+        let upheld = pool.solution_value == Some(new_value);
+        
+        // Set the new value
+        pool.solution_value = Some(new_value);
+        let amount = pool.settle_bonds(upheld)?;
+        let current_time = Clock::get()?.unix_timestamp;
+        pool.end_dispute_round(current_time)?;
+        emit_dispute_resolved(pool, upheld, current_time)?;
+        
+        let recipient = if upheld {
+            ctx.accounts.authority_collateral.to_account_info()
+        } else {
+            ctx.accounts.disputer_collateral.to_account_info()
+        };
+        pay_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.bond_escrow,
+            &ctx.accounts.collateral_mint,
+            recipient,
+            &ctx.accounts.collateral_token_program,
+            amount,
+        )
+    }
+
+    pub fn initialize_jury_registry(
+        ctx: Context<InitializeJuryRegistry>,
+        min_stake: u64,
+        jury_size: u8,
+        commit_period_seconds: i64,
+        reveal_period_seconds: i64,
+        slash_bps: u64,
+    ) -> Result<()> {
+        require!(
+            jury_size % 2 == 1 && jury_size as usize <= MAX_JURY_SIZE,
+            BettingPoolError::InvalidJuryConfig
+        );
+        require!(
+            commit_period_seconds > 0 && reveal_period_seconds > 0 && slash_bps <= BPS_DENOMINATOR,
+            BettingPoolError::InvalidJuryConfig
+        );
+        
+        let registry = &mut ctx.accounts.registry;
+        registry.governance_mint = ctx.accounts.governance_mint.key();
+        registry.stake_vault = ctx.accounts.stake_vault.key();
+        registry.min_stake = min_stake;
+        registry.jury_size = jury_size;
+        registry.commit_period_seconds = commit_period_seconds;
+        registry.reveal_period_seconds = reveal_period_seconds;
+        registry.slash_bps = slash_bps;
+        registry.jurors = Vec::with_capacity(MAX_JURORS);
+        registry.bump = ctx.bumps.registry;
+        
+        Ok(())
+    }
+
+    pub fn stake_juror(ctx: Context<StakeJuror>, amount: u64) -> Result<()> {
+        require!(amount > 0, BettingPoolError::InvalidAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.juror_tokens.to_account_info(),
+            mint: ctx.accounts.governance_mint.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.juror.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.governance_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.governance_mint.decimals)?;
+        
+        let registry = &mut ctx.accounts.registry;
+        let juror = ctx.accounts.juror.key();
+        if let Ok(entry) = registry.entry_mut(&juror) {
+            entry.stake = entry.stake.checked_add(amount).ok_or(BettingPoolError::MathOverflow)?;
+        } else {
+            require!(registry.jurors.len() < MAX_JURORS, BettingPoolError::JuryRegistryFull);
+            registry.jurors.push(JurorEntry {
+                juror,
+                stake: amount,
+                open_panels: 0,
+            });
+        }
+        
+        Ok(())
+    }
+
+    pub fn unstake_juror(ctx: Context<UnstakeJuror>, amount: u64) -> Result<()> {
+        require!(amount > 0, BettingPoolError::InvalidAmount);
+        
+        let registry = &mut ctx.accounts.registry;
+        let entry = registry.entry_mut(&ctx.accounts.juror.key())?;
+        require!(entry.open_panels == 0, BettingPoolError::JurorStakeLocked);
+        entry.stake = entry.stake.checked_sub(amount).ok_or(BettingPoolError::InsufficientStake)?;
+        registry.jurors.retain(|entry| entry.stake > 0);
+        
+        let registry_seeds = &[b"jury_registry".as_ref(), &[registry.bump]];
+        let signer = &[&registry_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            mint: ctx.accounts.governance_mint.to_account_info(),
+            to: ctx.accounts.juror_tokens.to_account_info(),
+            authority: registry.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.governance_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.governance_mint.decimals)?;
+        
+        Ok(())
+    }
+
+    pub fn enable_jury_resolution(ctx: Context<EnableJuryResolution>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.market_kind == MarketKind::Categorical, BettingPoolError::InvalidMarketKind);
+        require!(pool.resolution_source == ResolutionSource::Manual, BettingPoolError::ManualResolutionDisabled);
+        
+        // Bettors must know who settles disputes before betting closes
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < pool.end_time, BettingPoolError::BettingPeriodEnded);
+        
+        pool.jury_resolution = true;
+        
+        Ok(())
+    }
+
+    pub fn draw_jury(ctx: Context<DrawJury>) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        require!(pool.jury_resolution, BettingPoolError::JuryResolutionDisabled);
+        require!(pool.is_disputed, BettingPoolError::NotDisputed);
+        
+        // The seed is a slot hash from after the dispute was opened, so neither side
+        // could know it, and neither side can sit on the jury
+        let clock = Clock::get()?;
+        let seed = jury_seed(
+            &pool.key(),
+            pool.dispute_slot,
+            clock.slot,
+            &ctx.accounts.slot_hashes.to_account_info(),
+        )?;
+        let excluded = [pool.operator, pool.disputer.unwrap_or_default()];
+        let registry = &mut ctx.accounts.registry;
+        let seats = registry.draw_panel(seed, &excluded)?;
+        
+        let commit_deadline = clock
+            .unix_timestamp
+            .checked_add(registry.commit_period_seconds)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let reveal_deadline = commit_deadline
+            .checked_add(registry.reveal_period_seconds)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        let panel = &mut ctx.accounts.panel;
+        panel.pool = pool.key();
+        panel.payer = ctx.accounts.payer.key();
+        panel.commit_deadline = commit_deadline;
+        panel.reveal_deadline = reveal_deadline;
+        panel.seats = seats;
+        panel.bump = ctx.bumps.panel;
+        
+        Ok(())
     }
+
+    pub fn commit_jury_vote(ctx: Context<CommitJuryVote>, commitment: [u8; 32]) -> Result<()> {
+        let panel = &mut ctx.accounts.panel;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < panel.commit_deadline, BettingPoolError::CommitPeriodEnded);
+        
+        // A juror may replace its commitment until the commit window closes
+        panel.seat_mut(&ctx.accounts.juror.key())?.commitment = Some(commitment);
+        
+        Ok(())
+    }
+
+    pub fn reveal_jury_vote(ctx: Context<RevealJuryVote>, outcome: u8, salt: [u8; 32]) -> Result<()> {
+        let panel = &mut ctx.accounts.panel;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time >= panel.commit_deadline, BettingPoolError::CommitPeriodNotEnded);
+        require!(current_time < panel.reveal_deadline, BettingPoolError::RevealPeriodEnded);
+        require!(ctx.accounts.pool.is_valid_answer(outcome), BettingPoolError::InvalidOutcome);
+        
+        let juror = ctx.accounts.juror.key();
+        let seat = panel.seat_mut(&juror)?;
+        require!(
+            seat.commitment == Some(vote_commitment(&juror, outcome, &salt)),
+            BettingPoolError::CommitmentMismatch
+        );
+        seat.vote = Some(outcome);
+        
+        Ok(())
+    }
+
+    pub fn tally_jury_votes(ctx: Context<TallyJuryVotes>) -> Result<()> {
+        let panel = &ctx.accounts.panel;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time >= panel.reveal_deadline, BettingPoolError::RevealPeriodNotEnded);
+        
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.is_disputed, BettingPoolError::NotDisputed);
+        let proposed = pool.solution_winner.ok_or(BettingPoolError::NoSolutionProposed)?;
+        
+        let winner = panel.verdict(proposed);
+        ctx.accounts.registry.settle_panel(panel, winner)?;
+        
+        let upheld = winner == proposed;
+        pool.solution_winner = Some(winner);
+        let amount = pool.settle_bonds(upheld)?;
+        pool.end_dispute_round(current_time)?;
+        
+        msg!("Jury decided outcome {}", winner);
+        emit_dispute_resolved(pool, upheld, current_time)?;
+        
+        let recipient = if upheld {
+            ctx.accounts.authority_collateral.to_account_info()
+        } else {
+            ctx.accounts.disputer_collateral.to_account_info()
+        };
+        pay_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.bond_escrow,
+            &ctx.accounts.collateral_mint,
+            recipient,
+            &ctx.accounts.collateral_token_program,
+            amount,
+        )
+    }
+
+    pub fn finalize_pool(ctx: Context<FinalizePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        
+        require!(pool.solution_proposed, BettingPoolError::NoSolutionProposed);
+        require!(!pool.is_finalized, BettingPoolError::AlreadyFinalized);
+        require!(clock.unix_timestamp > pool.dispute_period_end, BettingPoolError::DisputePeriodNotEnded);
+        require!(!pool.is_disputed, BettingPoolError::PoolIsDisputed);
+        
+        // Split the pot between the paying outcomes and set the pool as finalized
+        pool.settle_payouts()?;
+        pool.is_finalized = true;
+        emit_if_finalized(pool, clock.unix_timestamp);
+        
+        Ok(())
+    }
+
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        require!(pool.is_finalized, BettingPoolError::PoolNotFinalized);
+        
+        let winner = pool
+            .outcome_index(&ctx.accounts.winning_mint.key())
+            .ok_or(BettingPoolError::InvalidOutcome)?;
+        
+        // Check if user holds winning tokens
+        let winning_amount = ctx.accounts.user_winning_token.amount;
+        require!(winning_amount > 0, BettingPoolError::NoWinningTokens);
+        
+        let gross_payout = pool.take_payout(winner, winning_amount)?;
+        let payout = pool.charge_fees(gross_payout)?;
+        
+        let position = &mut ctx.accounts.position;
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.position);
+        // Each outcome pays a user once
+        position.record_claim(winner, payout)?;
+        
+        // Burn the winning tokens, so they cannot be claimed again
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.winning_mint.to_account_info(),
+            from: ctx.accounts.user_winning_token.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token_interface::burn(cpi_ctx, winning_amount)?;
+        
+        // The burn empties the token account, so hand its rent back to the user
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.user_winning_token.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::close_account(CpiContext::new(cpi_program, cpi_accounts))?;
+        
+        // Pay out the user's share of the collateral
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.user_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, payout, ctx.accounts.collateral_mint.decimals)?;
+        
+        msg!("User claimed {} collateral for {} winning tokens", payout, winning_amount);
+        emit!(WinningsClaimed {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            outcome: winner,
+            tokens: winning_amount,
+            payout,
+            fees: gross_payout.checked_sub(payout).ok_or(BettingPoolError::MathOverflow)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn withdraw_proposer_bond(ctx: Context<WithdrawProposerBond>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.is_finalized, BettingPoolError::PoolNotFinalized);
+        
+        // Whatever survived the disputes goes back to the authority
+        let amount = pool.proposer_bond;
+        require!(amount > 0, BettingPoolError::NoBondToWithdraw);
+        pool.proposer_bond = 0;
+        
+        pay_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.bond_escrow,
+            &ctx.accounts.collateral_mint,
+            ctx.accounts.authority_collateral.to_account_info(),
+            &ctx.accounts.collateral_token_program,
+            amount,
+        )?;
+        
+        emit!(ProposerBondWithdrawn {
+            pool: ctx.accounts.pool.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let recipient = ctx.accounts.recipient.key();
+        require!(
+            recipient == pool.operator || recipient == ctx.accounts.config.treasury,
+            BettingPoolError::Unauthorized
+        );
+        
+        // The operator collects the creator fees and the treasury the protocol fees
+        let mut amount: u64 = 0;
+        if recipient == pool.operator {
+            amount = amount
+                .checked_add(std::mem::take(&mut pool.creator_fees))
+                .ok_or(BettingPoolError::MathOverflow)?;
+        }
+        if recipient == ctx.accounts.config.treasury {
+            amount = amount
+                .checked_add(std::mem::take(&mut pool.protocol_fees))
+                .ok_or(BettingPoolError::MathOverflow)?;
+        }
+        require!(amount > 0, BettingPoolError::NoFeesToWithdraw);
+        
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.recipient_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.collateral_mint.decimals)?;
+        
+        emit!(FeesWithdrawn {
+            pool: ctx.accounts.pool.key(),
+            recipient,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn claim_amm_liquidity(ctx: Context<ClaimAmmLiquidity>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        
+        require!(pool.is_finalized, BettingPoolError::PoolNotFinalized);
+        let reserves = pool.amm.as_ref().ok_or(BettingPoolError::AmmNotFunded)?.reserves;
+        
+        // The market maker's reserves are claimed like any other winning tokens
+        let mut payout: u64 = 0;
+        for (index, reserve) in reserves.into_iter().enumerate() {
+            if reserve > 0 && pool.is_claimable_outcome(index as u8) {
+                let share = pool.take_payout(index as u8, reserve)?;
+                payout = payout.checked_add(share).ok_or(BettingPoolError::MathOverflow)?;
+            }
+        }
+        require!(payout > 0, BettingPoolError::NoWinningTokens);
+        if let Some(amm) = pool.amm.as_mut() {
+            amm.reserves = [0, 0];
+        }
+        
+        let pool = &ctx.accounts.pool;
+        let market_id = pool.market_id.to_le_bytes();
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.authority.as_ref(),
+            market_id.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            mint: ctx.accounts.collateral_mint.to_account_info(),
+            to: ctx.accounts.authority_collateral.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.collateral_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, payout, ctx.accounts.collateral_mint.decimals)?;
+        
+        emit!(AmmLiquidityClaimed {
+            pool: ctx.accounts.pool.key(),
+            authority: ctx.accounts.authority.key(),
+            payout,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Bring a pool written by an older `POOL_VERSION` up to the current one. Pools
+    /// from before versioning cannot be migrated, see `LegacyBettingPool`.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool_info = ctx.accounts.pool.to_account_info();
+        let mut data = pool_info.try_borrow_mut_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *BettingPool::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(data.len() != LegacyBettingPool::SPACE, BettingPoolError::LegacyPoolUnsupported);
+        
+        let mut pool = BettingPool::try_deserialize(&mut &data[..])?;
+        require!(pool.version < POOL_VERSION, BettingPoolError::PoolAlreadyMigrated);
+        
+        // Fields added since were carved from the zeroed reserved bytes, so they
+        // already read as their defaults and only the version changes
+        pool.version = POOL_VERSION;
+        pool.try_serialize(&mut &mut data[..])
+    }
+
+    // remaining accounts: outcome mints to close, skipped while tokens are outstanding
+    pub fn close_pool<'info>(ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.is_finalized, BettingPoolError::PoolNotFinalized);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let grace_period_end = pool
+            .dispute_period_end
+            .checked_add(POOL_CLOSE_GRACE_PERIOD_SECONDS)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        require!(
+            pool.claims_complete() || current_time >= grace_period_end,
+            BettingPoolError::ClaimsOutstanding
+        );
+        
+        // The operator is paid what it is still owed and the treasury takes the rest,
+        // protocol fees and unclaimed winnings included
+        let creator_fees = std::mem::take(&mut pool.creator_fees);
+        let proposer_bond = std::mem::take(&mut pool.proposer_bond);
+        let vault_dust = ctx
+            .accounts
+            .collateral_vault
+            .amount
+            .checked_sub(creator_fees)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let escrow_dust = ctx
+            .accounts
+            .bond_escrow
+            .amount
+            .checked_sub(proposer_bond)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        let pool = &ctx.accounts.pool;
+        let mint = &ctx.accounts.collateral_mint;
+        let collateral_token_program = &ctx.accounts.collateral_token_program;
+        let authority_collateral = ctx.accounts.authority_collateral.to_account_info();
+        let treasury_collateral = ctx.accounts.treasury_collateral.to_account_info();
+        let vault = &ctx.accounts.collateral_vault;
+        let escrow = &ctx.accounts.bond_escrow;
+        pay_from_pool(pool, vault, mint, authority_collateral.clone(), collateral_token_program, creator_fees)?;
+        pay_from_pool(pool, vault, mint, treasury_collateral.clone(), collateral_token_program, vault_dust)?;
+        pay_from_pool(pool, escrow, mint, authority_collateral, collateral_token_program, proposer_bond)?;
+        pay_from_pool(pool, escrow, mint, treasury_collateral, collateral_token_program, escrow_dust)?;
+        
+        // Rent of everything the creator paid for goes back to it
+        let creator = ctx.accounts.creator.to_account_info();
+        for account in [vault.to_account_info(), escrow.to_account_info()] {
+            close_pool_account(pool, account, creator.clone(), collateral_token_program.to_account_info())?;
+        }
+        for outcome_mint in ctx.remaining_accounts {
+            require!(
+                pool.outcomes.iter().any(|outcome| outcome.mint == outcome_mint.key()),
+                BettingPoolError::InvalidOutcomeAccounts
+            );
+            if InterfaceAccount::<Mint>::try_from(outcome_mint)?.supply == 0 {
+                close_pool_account(
+                    pool,
+                    outcome_mint.clone(),
+                    creator.clone(),
+                    ctx.accounts.token_program.to_account_info(),
+                )?;
+            }
+        }
+        
+        emit!(PoolClosed {
+            pool: pool.key(),
+            swept: vault_dust.checked_add(escrow_dust).ok_or(BettingPoolError::MathOverflow)?,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // Only whoever can upgrade the program may claim the config
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Project5Capstone>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ BettingPoolError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalConfig::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        constraint = admin.key() == config.admin @ BettingPoolError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        constraint = guardian.key() == config.guardian @ BettingPoolError::Unauthorized
+    )]
+    pub guardian: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    // Only this pool is affected when given, every pool otherwise
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Option<Account<'info, BettingPool>>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool", authority.key().as_ref(), market_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + BettingPool::INIT_SPACE
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(mint::token_program = collateral_token_program)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = pool,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    // Holds proposer and dispute bonds apart from the betting collateral
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = pool,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome_index: u8)]
+pub struct InitializeOutcome<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    // Outcome mints share the collateral decimals so 1 token is backed by 1 collateral unit
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome_index]],
+        bump,
+        mint::decimals = collateral_mint.decimals,
+        mint::authority = pool,
+        mint::token_program = token_program,
+        extensions::close_authority::authority = pool,
+        extensions::transfer_hook::authority = pool,
+        extensions::metadata_pointer::authority = pool,
+        extensions::metadata_pointer::metadata_address = outcome_mint
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct UpdateOutcomeUri<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableTransferHook<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, outcome: u8)]
+pub struct MintPredictionTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_outcome_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, outcome: u8)]
+pub struct BurnPredictionTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_outcome_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// Each outcome's mint and the user's token account for it are passed in order as
+// remaining accounts: [mint 0, token 0, mint 1, token 1, ...]
+#[derive(Accounts)]
+pub struct MintCompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// Takes the same remaining accounts as MintCompleteSet
+#[derive(Accounts)]
+pub struct RedeemCompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundAmm<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
+    )]
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct BuyOutcomeTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_outcome_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct SellOutcomeTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_outcome_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        constraint = pool.pending_operator == Some(new_operator.key()) @ BettingPoolError::Unauthorized
+    )]
+    pub new_operator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCreatorFee<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureBonds<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
+    )]
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(winner: u8)]
+pub struct ProposeSolution<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeScalarSolution<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
 }
 
 #[derive(Accounts)]
-pub struct InitializePool<'info> {
+pub struct ResolveFromOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    /// CHECK: owner, discriminator and key are checked against the pool's resolution source
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct DisputeSolution<'info> {
     #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        seeds = [b"outcome_mint", pool.key().as_ref(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_outcome_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_winner: u8)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
+    )]
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+        constraint = pool.disputer == Some(disputer_collateral.owner) @ BettingPoolError::InvalidBondAccount
+    )]
+    pub disputer_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: the pool's jury panel, only read to tell whether it was drawn
+    #[account(seeds = [b"jury_panel", pool.key().as_ref()], bump)]
+    pub panel: UncheckedAccount<'info>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveScalarDispute<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
     pub authority: Signer<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
+    )]
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+        constraint = pool.disputer == Some(disputer_collateral.owner) @ BettingPoolError::InvalidBondAccount
+    )]
+    pub disputer_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJuryRegistry<'info> {
+    // The registry settles disputes for every jury-resolved pool, so only the admin
+    // sets it up
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ BettingPoolError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
         init,
-        payer = authority,
-        seeds = [b"pool", authority.key().as_ref()],
+        payer = admin,
+        space = JuryRegistry::SPACE,
+        seeds = [b"jury_registry"],
+        bump
+    )]
+    pub registry: Account<'info, JuryRegistry>,
+    
+    pub governance_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"jury_stake_vault"],
         bump,
-        space = BettingPool::space()
+        token::mint = governance_mint,
+        token::authority = registry,
+        token::token_program = governance_token_program
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub governance_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct StakeJuror<'info> {
+    pub juror: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, JuryRegistry>,
+    
+    #[account(constraint = governance_mint.key() == registry.governance_mint)]
+    pub governance_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        address = registry.stake_vault
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = juror,
+        token::token_program = governance_token_program
+    )]
+    pub juror_tokens: InterfaceAccount<'info, TokenAccount>,
+    
+    pub governance_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeJuror<'info> {
+    pub juror: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, JuryRegistry>,
+    
+    #[account(constraint = governance_mint.key() == registry.governance_mint)]
+    pub governance_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        address = registry.stake_vault
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = juror,
+        token::token_program = governance_token_program
+    )]
+    pub juror_tokens: InterfaceAccount<'info, TokenAccount>,
+    
+    pub governance_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EnableJuryResolution<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+pub struct DrawJury<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, JuryRegistry>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = JuryPanel::SPACE,
+        seeds = [b"jury_panel", pool.key().as_ref()],
+        bump
+    )]
+    pub panel: Account<'info, JuryPanel>,
+    
+    /// CHECK: the SlotHashes sysvar, read by hand as it is too large to deserialize
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitJuryVote<'info> {
+    pub juror: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_panel", pool.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Account<'info, JuryPanel>,
+}
+
+#[derive(Accounts)]
+pub struct RevealJuryVote<'info> {
+    pub juror: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_panel", pool.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Account<'info, JuryPanel>,
+}
+
+#[derive(Accounts)]
+pub struct TallyJuryVotes<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
     )]
     pub pool: Account<'info, BettingPool>,
     
     #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = pool,
-        mint::token_program = token_program
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, JuryRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"jury_panel", pool.key().as_ref()],
+        bump = panel.bump,
+        close = payer
+    )]
+    pub panel: Account<'info, JuryPanel>,
+    
+    /// CHECK: only receives the panel's rent, checked against the panel
+    #[account(
+        mut,
+        address = panel.payer
+    )]
+    pub payer: UncheckedAccount<'info>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = pool.operator,
+        token::token_program = collateral_token_program
+    )]
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+        constraint = pool.disputer == Some(disputer_collateral.owner) @ BettingPoolError::InvalidBondAccount
+    )]
+    pub disputer_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePool<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
     )]
-    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub pool: Account<'info, BettingPool>,
     
     #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = pool,
-        mint::token_program = token_program
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
     )]
-    pub no_mint: InterfaceAccount<'info, Mint>,
-    
-    pub token_program: Program<'info, Token2022>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    pub config: Account<'info, GlobalConfig>,
 }
 
 #[derive(Accounts)]
-#[instruction(amount: u64, prediction: bool)]
-pub struct MintPredictionTokens<'info> {
+pub struct ClaimWinnings<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
     )]
     pub pool: Account<'info, BettingPool>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::SPACE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
     #[account(
         mut,
-        constraint = yes_mint.key() == pool.yes_mint
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
     )]
-    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = no_mint.key() == pool.no_mint
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
     )]
-    pub no_mint: InterfaceAccount<'info, Mint>,
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
     
+    // The finalized outcome's mint, or either side's mint in a scalar market
     #[account(
-        init_if_needed,
-        payer = user,
-        associated_token::mint = yes_mint,
-        associated_token::authority = user,
-        associated_token::token_program = token_program
+        mut,
+        constraint = pool.is_claimable_mint(&winning_mint.key()) @ BettingPoolError::InvalidOutcome
     )]
-    pub user_yes_token: InterfaceAccount<'info, TokenAccount>,
+    pub winning_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
-        init_if_needed,
-        payer = user,
-        associated_token::mint = no_mint,
+        mut,
+        associated_token::mint = winning_mint,
         associated_token::authority = user,
         associated_token::token_program = token_program
     )]
-    pub user_no_token: InterfaceAccount<'info, TokenAccount>,
+    pub user_winning_token: InterfaceAccount<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token2022>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(amount: u64, prediction: bool)]
-pub struct BurnPredictionTokens<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+pub struct WithdrawProposerBond<'info> {
+    #[account(
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"pool", pool.authority.as_ref()],
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
         bump = pool.bump
     )]
     pub pool: Account<'info, BettingPool>,
     
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
+    
     #[account(
         mut,
-        constraint = yes_mint.key() == pool.yes_mint
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
     )]
-    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
+    
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub recipient: Signer<'info>,
     
+    // Fees come out of the same vault as winnings, so they wait out a pause too
     #[account(
         mut,
-        constraint = no_mint.key() == pool.no_mint
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
+    )]
+    pub pool: Account<'info, BettingPool>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
     )]
-    pub no_mint: InterfaceAccount<'info, Mint>,
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
-        associated_token::mint = yes_mint,
-        associated_token::authority = user
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
     )]
-    pub user_yes_token: InterfaceAccount<'info, TokenAccount>,
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        associated_token::mint = no_mint,
-        associated_token::authority = user
+        token::mint = collateral_mint,
+        token::authority = recipient,
+        token::token_program = collateral_token_program
     )]
-    pub user_no_token: InterfaceAccount<'info, TokenAccount>,
+    pub recipient_collateral: InterfaceAccount<'info, TokenAccount>,
     
-    pub token_program: Program<'info, Token2022>,
-    pub system_program: Program<'info, System>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-#[instruction(winner: bool)]
-pub struct ProposeSolution<'info> {
+pub struct ClaimAmmLiquidity<'info> {
     #[account(
-        constraint = authority.key() == pool.authority @ BettingPoolError::Unauthorized
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
     )]
     pub pool: Account<'info, BettingPool>,
-}
-
-#[derive(Accounts)]
-pub struct DisputeSolution<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
     
     #[account(
-        mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
     )]
-    pub pool: Account<'info, BettingPool>,
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
-        associated_token::mint = yes_mint,
-        associated_token::authority = user
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
     )]
-    pub user_yes_token: InterfaceAccount<'info, TokenAccount>,
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        associated_token::mint = no_mint,
-        associated_token::authority = user
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
     )]
-    pub user_no_token: InterfaceAccount<'info, TokenAccount>,
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(constraint = yes_mint.key() == pool.yes_mint)]
-    pub yes_mint: InterfaceAccount<'info, Mint>,
-    
-    #[account(constraint = no_mint.key() == pool.no_mint)]
-    pub no_mint: InterfaceAccount<'info, Mint>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-#[instruction(new_winner: bool)]
-pub struct ResolveDispute<'info> {
+pub struct MigratePool<'info> {
+    /// CHECK: checked by hand so an unsupported layout gets its own error
+    #[account(mut, owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
     #[account(
-        constraint = authority.key() == pool.authority @ BettingPoolError::Unauthorized
+        constraint = authority.key() == pool.operator @ BettingPoolError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
+    /// CHECK: the pool creator, who paid for and gets back the rent
+    #[account(mut, address = pool.authority)]
+    pub creator: UncheckedAccount<'info>,
+    
     #[account(
         mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        close = creator,
+        seeds = [b"pool", pool.authority.as_ref(), pool.market_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        constraint = !pool.pause.paused @ BettingPoolError::PoolPaused
     )]
     pub pool: Account<'info, BettingPool>,
-}
-
-#[derive(Accounts)]
-pub struct FinalizePool<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
     
     #[account(
-        mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.pause.paused @ BettingPoolError::ProtocolPaused
     )]
-    pub pool: Account<'info, BettingPool>,
-}
-
-#[derive(Accounts)]
-pub struct ClaimWinnings<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(constraint = collateral_mint.key() == pool.collateral_mint)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
-        seeds = [b"pool", pool.authority.as_ref()],
-        bump = pool.bump
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
     )]
-    pub pool: Account<'info, BettingPool>,
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(mut, constraint = yes_mint.key() == pool.yes_mint)]
-    pub yes_mint: InterfaceAccount<'info, Mint>,
-    
-    #[account(mut, constraint = no_mint.key() == pool.no_mint)]
-    pub no_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"bond_escrow", pool.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program
+    )]
+    pub bond_escrow: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        associated_token::mint = yes_mint,
-        associated_token::authority = user
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program
     )]
-    pub user_yes_token: InterfaceAccount<'info, TokenAccount>,
+    pub authority_collateral: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
-        associated_token::mint = no_mint,
-        associated_token::authority = user
+        token::mint = collateral_mint,
+        token::authority = config.treasury,
+        token::token_program = collateral_token_program
     )]
-    pub user_no_token: InterfaceAccount<'info, TokenAccount>,
+    pub treasury_collateral: InterfaceAccount<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token2022>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Program-wide settings, one account for every pool. Only the admin can change them.
+#[account]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub treasury: Pubkey, // withdraws the protocol fees of every pool
+    pub protocol_fee_bps: u64, // charged on claims in pools created from now on
+    pub min_dispute_period_seconds: i64,
+    pub max_dispute_period_seconds: i64,
+    pub allowed_collateral_mints: Vec<Pubkey>, // empty allows any mint
+    pub pool_creation_paused: bool,
+    pub guardian: Pubkey, // can pause trading and claims in one pool or all of them
+    pub unpause_delay_seconds: i64,
+    pub pause: PauseState, // applies to every pool
+    pub bump: u8,
+}
+
+impl GlobalConfig {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // admin: Pubkey
+        32 + // treasury: Pubkey
+        8 +  // protocol_fee_bps: u64
+        8 +  // min_dispute_period_seconds: i64
+        8 +  // max_dispute_period_seconds: i64
+        4 + MAX_COLLATERAL_MINTS * 32 + // allowed_collateral_mints: Vec<Pubkey>
+        1 +  // pool_creation_paused: bool
+        32 + // guardian: Pubkey
+        8 +  // unpause_delay_seconds: i64
+        PauseState::INIT_SPACE + // pause: PauseState
+        1; // bump: u8
+    
+    pub fn is_collateral_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_collateral_mints.is_empty() || self.allowed_collateral_mints.contains(mint)
+    }
+}
+
+/// Circuit breaker over minting, burning, trading, disputes and claims. Pausing is
+/// immediate; unpausing waits out a delay after it is requested.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PauseState {
+    pub paused: bool,
+    pub unpause_at: Option<i64>, // set once an unpause is requested
+}
+
+impl PauseState {
+    /// Pause at once, cancelling any pending unpause
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.unpause_at = None;
+    }
+
+    pub fn request_unpause(&mut self, now: i64, delay: i64) -> Result<()> {
+        require!(self.paused, BettingPoolError::NotPaused);
+        self.unpause_at = Some(now.checked_add(delay).ok_or(BettingPoolError::MathOverflow)?);
+        Ok(())
+    }
+
+    pub fn unpause(&mut self, now: i64) -> Result<()> {
+        require!(
+            self.unpause_at.is_some_and(|unpause_at| now >= unpause_at),
+            BettingPoolError::UnpauseTimelocked
+        );
+        self.paused = false;
+        self.unpause_at = None;
+        Ok(())
+    }
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct BettingPool {
-    pub authority: Pubkey,
-    pub yes_mint: Pubkey,
-    pub no_mint: Pubkey,
-    pub total_yes_tokens: u64,
-    pub total_no_tokens: u64,
+    pub authority: Pubkey, // creator, part of the pool address so it never changes
+    pub operator: Pubkey, // signs for the pool, starts as the authority
+    pub pending_operator: Option<Pubkey>, // takes over as operator once it accepts
+    pub market_id: u64,
+    pub collateral_mint: Pubkey,
+    pub collateral_vault: Pubkey,
+    pub total_collateral: u64,
+    pub complete_sets: u64, // sets backed once for all outcomes, included in each total_tokens
+    pub protocol_fee_bps: u64, // copied from the global config at creation
+    pub creator_fee_bps: u64,
+    pub protocol_fees: u64, // accrued in the vault until the treasury withdraws them
+    pub creator_fees: u64, // accrued in the vault until the operator withdraws them
+    pub market_kind: MarketKind,
+    pub resolution_source: ResolutionSource,
+    pub outcome_count: u8,
+    #[max_len(MAX_OUTCOMES)]
+    pub outcomes: Vec<Outcome>, // indexed by outcome, filled by initialize_outcome
     pub solution_proposed: bool,
-    pub solution_winner: Option<bool>, // true for YES, false for NO
+    pub solution_winner: Option<u8>, // index into outcomes or INVALID_OUTCOME, categorical markets only
+    pub solution_value: Option<i64>, // resolved value, scalar markets only
     pub dispute_period_start: i64,
     pub dispute_period_end: i64,
     pub dispute_period_seconds: i64,
     pub dispute_threshold: u64,
+    pub proposer_bond: u64, // escrowed by the authority behind its proposals
+    pub dispute_bond: u64, // posted by the first disputer, escalating each round
+    pub jury_resolution: bool, // disputes are settled by a drawn jury, not the authority
+    pub transfer_hook: bool, // outcome mints call the transfer hook program
+    pub dispute_round: u8, // disputes resolved so far
     pub is_disputed: bool,
     pub is_finalized: bool,
+    pub pause: PauseState,
     pub bump: u8,
+    #[max_len(MAX_POOL_NAME_LEN)]
     pub name: String,
+    #[max_len(MAX_POOL_DESCRIPTION_LEN)]
     pub description: String,
     pub end_time: i64,
     pub created_at: i64,
     pub disputer: Option<Pubkey>,
+    pub amm: Option<AmmState>, // constant-product market maker, binary markets only
+    pub version: u8, // layout version, POOL_VERSION once created or migrated
+    pub disputed_at: i64, // when the current dispute was opened
+    pub dispute_slot: u64, // slot the current dispute was opened in, fixes its jury's seed
+    pub reserved: [u8; BettingPool::RESERVED_SPACE], // zeroed room for fields added later
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MarketKind {
+    /// One of `outcome_count` outcomes wins the whole pot
+    Categorical,
+    /// LONG and SHORT split the pot linearly by where the resolved value falls
+    /// between the bounds
+    Scalar { lower_bound: i64, upper_bound: i64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ResolutionSource {
+    /// The authority proposes the answer and disputes decide it
+    Manual,
+    /// Anyone can resolve from the price feed once betting has ended. Binary markets
+    /// compare the price to `strike`; scalar markets resolve to the price itself.
+    /// If nobody has after `ORACLE_RESOLUTION_TIMEOUT_SECONDS`, the authority can
+    /// propose the answer instead.
+    Oracle {
+        provider: OracleProvider,
+        price_feed: Pubkey,
+        strike: i64,
+        comparison: Comparison,
+        max_staleness_seconds: i64, // latest a price can be published after end_time
+        max_confidence_bps: u64,
+    },
+}
+
+/// Where an oracle-resolved pool reads its price. Prices and strikes are compared
+/// in the provider's units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OracleProvider {
+    /// A Pyth `PriceUpdateV2` account, in the feed's own exponent
+    Pyth,
+    /// A Switchboard on-demand pull feed, rescaled to `decimals` places
+    Switchboard { decimals: u8 },
+}
+
+// Room for parameters of providers added later, so the pool layout stays put
+impl Space for OracleProvider {
+    const INIT_SPACE: usize = 1 + 32;
+}
+
+/// How an oracle price is compared to the strike. Outcome 0 wins when it holds.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Comparison {
+    Above,
+    AtOrAbove,
+    Below,
+    AtOrBelow,
+}
+
+impl Comparison {
+    pub fn holds(&self, price: i64, strike: i64) -> bool {
+        match self {
+            Comparison::Above => price > strike,
+            Comparison::AtOrAbove => price >= strike,
+            Comparison::Below => price < strike,
+            Comparison::AtOrBelow => price <= strike,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Outcome {
+    pub mint: Pubkey,
+    #[max_len(MAX_OUTCOME_LABEL_LEN)]
+    pub label: String,
+    pub total_tokens: u64,
+    pub payout_pool: u64, // collateral still claimable by holders, set on finalization
+}
+
+/// Constant-product market maker over the two outcomes of a binary market. The
+/// reserves are complete sets the pool holds without minting them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct AmmState {
+    pub reserves: [u64; 2],
+    pub implied_probability_bps: u64, // price of outcome 0, in basis points
+}
+
+impl AmmState {
+    pub fn new(liquidity: u64) -> Self {
+        AmmState {
+            reserves: [liquidity, liquidity],
+            implied_probability_bps: BPS_DENOMINATOR / 2,
+        }
+    }
+
+    /// Buy `amount` tokens of `outcome` and return the collateral it costs, rounded
+    /// up. The collateral mints complete sets into both reserves and `amount` leaves
+    /// the bought side, keeping the product of the reserves constant.
+    pub fn buy(&mut self, outcome: u8, amount: u64) -> Result<u64> {
+        let (bought, other) = Self::sides(outcome)?;
+        let reserve = self.reserves[bought] as i128;
+        let other_reserve = self.reserves[other] as i128;
+        
+        // cost^2 + (reserve + other_reserve - amount) * cost - amount * other_reserve = 0
+        let b = reserve + other_reserve - amount as i128;
+        let discriminant = b
+            .unsigned_abs()
+            .checked_pow(2)
+            .and_then(|square| {
+                (4 * amount as u128)
+                    .checked_mul(other_reserve as u128)
+                    .and_then(|product| square.checked_add(product))
+            })
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let root = isqrt_ceil(discriminant) as i128;
+        let cost = to_u64(mul_div(
+            u128::try_from(root - b).map_err(|_| error!(BettingPoolError::MathOverflow))?,
+            1,
+            2,
+            Rounding::Up,
+        )?)?;
+        
+        self.reserves[bought] = (self.reserves[bought] as u128 + cost as u128)
+            .checked_sub(amount as u128)
+            .and_then(|reserve| u64::try_from(reserve).ok())
+            .ok_or(BettingPoolError::InsufficientLiquidity)?;
+        self.reserves[other] = self.reserves[other]
+            .checked_add(cost)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        self.update_probability();
+        
+        Ok(cost)
+    }
+
+    /// Sell `amount` tokens of `outcome` back to the reserve and return the collateral
+    /// paid for them, rounded down. The proceeds are complete sets redeemed out of
+    /// both reserves, keeping the product of the reserves constant.
+    pub fn sell(&mut self, outcome: u8, amount: u64) -> Result<u64> {
+        let (sold, other) = Self::sides(outcome)?;
+        let reserve = self.reserves[sold] as u128;
+        let other_reserve = self.reserves[other] as u128;
+        
+        // proceeds^2 - (reserve + other_reserve + amount) * proceeds + amount * other_reserve = 0
+        let b = reserve + other_reserve + amount as u128;
+        let discriminant = b
+            .checked_pow(2)
+            .and_then(|square| {
+                (4 * amount as u128)
+                    .checked_mul(other_reserve)
+                    .and_then(|product| square.checked_sub(product))
+            })
+            .ok_or(BettingPoolError::MathOverflow)?;
+        let proceeds = u64::try_from((b - isqrt_ceil(discriminant)) / 2)
+            .map_err(|_| error!(BettingPoolError::MathOverflow))?;
+        
+        self.reserves[sold] = u64::try_from(reserve + amount as u128 - proceeds as u128)
+            .map_err(|_| error!(BettingPoolError::MathOverflow))?;
+        self.reserves[other] = self.reserves[other]
+            .checked_sub(proceeds)
+            .ok_or(BettingPoolError::InsufficientLiquidity)?;
+        self.update_probability();
+        
+        Ok(proceeds)
+    }
+
+    fn sides(outcome: u8) -> Result<(usize, usize)> {
+        match outcome {
+            0 => Ok((0, 1)),
+            1 => Ok((1, 0)),
+            _ => err!(BettingPoolError::InvalidOutcome),
+        }
+    }
+
+    // An outcome is cheap when the pool holds a lot of it
+    fn update_probability(&mut self) {
+        let total = self.reserves[0] as u128 + self.reserves[1] as u128;
+        if let Ok(probability) = mul_div(self.reserves[1] as u128, BPS_DENOMINATOR as u128, total, Rounding::Down) {
+            self.implied_probability_bps = probability as u64;
+        }
+    }
+}
+
+/// Emit the outcome of the dispute round that just ended, and the finalization
+/// it caused if it was the last
+fn emit_dispute_resolved(pool: &Account<BettingPool>, upheld: bool, timestamp: i64) -> Result<()> {
+    emit!(DisputeResolved {
+        pool: pool.key(),
+        winner: pool.solution_winner,
+        value: pool.solution_value,
+        upheld,
+        round: pool.dispute_round.checked_sub(1).ok_or(BettingPoolError::MathOverflow)?,
+        timestamp,
+    });
+    emit_if_finalized(pool, timestamp);
+    Ok(())
+}
+
+fn emit_if_finalized(pool: &Account<BettingPool>, timestamp: i64) {
+    if pool.is_finalized {
+        emit!(PoolFinalized {
+            pool: pool.key(),
+            winner: pool.solution_winner,
+            value: pool.solution_value,
+            timestamp,
+        });
+    }
+}
+
+/// Transfer `amount` out of the pool's vault or bond escrow, signed by the pool
+fn pay_from_pool<'info>(
+    pool: &Account<'info, BettingPool>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    collateral_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    collateral_token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let market_id = pool.market_id.to_le_bytes();
+    let pool_seeds = &[
+        b"pool".as_ref(),
+        pool.authority.as_ref(),
+        market_id.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&pool_seeds[..]];
+    
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: collateral_mint.to_account_info(),
+        to,
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(collateral_token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, amount, collateral_mint.decimals)
+}
+
+/// Add enough lamports to `mint` for it to stay rent exempt once its token metadata
+/// is replaced by `metadata`
+fn fund_metadata_rent<'info>(
+    mint: &AccountInfo<'info>,
+    metadata: &TokenMetadata,
+    payer: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let new_len = {
+        let data = mint.try_borrow_data()?;
+        let current = StateWithExtensions::<MintState>::unpack(&data)?
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_or(Ok(0), |current| current.tlv_size_of())?;
+        data.len() - current + metadata.tlv_size_of()?
+    };
+    
+    let lamports = Rent::get()?.minimum_balance(new_len).saturating_sub(mint.lamports());
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = system_program::Transfer {
+        from: payer,
+        to: mint.clone(),
+    };
+    system_program::transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), lamports)
+}
+
+/// Create the extra account list of an outcome mint in the transfer hook program.
+/// The pool signs so nobody can register a different list for the mint first.
+fn initialize_transfer_hook_metas<'info>(
+    pool: &Account<'info, BettingPool>,
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    extra_account_metas: AccountInfo<'info>,
+    hook_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let market_id = pool.market_id.to_le_bytes();
+    let pool_seeds = &[
+        b"pool".as_ref(),
+        pool.authority.as_ref(),
+        market_id.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&pool_seeds[..]];
+    
+    let ix = Instruction {
+        program_id: OUTCOME_TRANSFER_HOOK_ID,
+        accounts: vec![
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(pool.key(), true),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new(extra_account_metas.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data: hashv(&[b"global:initialize_extra_account_metas"]).to_bytes()[..8].to_vec(),
+    };
+    invoke_signed(
+        &ix,
+        &[payer, pool.to_account_info(), mint, extra_account_metas, system_program, hook_program],
+        signer,
+    )
+    .map_err(Into::into)
+}
+
+/// Close a token account or mint the pool is the authority of, sending its rent
+/// to `destination`
+fn close_pool_account<'info>(
+    pool: &Account<'info, BettingPool>,
+    account: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    let market_id = pool.market_id.to_le_bytes();
+    let pool_seeds = &[
+        b"pool".as_ref(),
+        pool.authority.as_ref(),
+        market_id.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&pool_seeds[..]];
+    
+    let cpi_accounts = CloseAccount {
+        account,
+        destination,
+        authority: pool.to_account_info(),
+    };
+    token_interface::close_account(CpiContext::new_with_signer(token_program, cpi_accounts, signer))
+}
+
+/// Split remaining accounts into (outcome mint, user token account) pairs, checking
+/// that they cover every outcome of `pool` in order and belong to `user`
+fn outcome_token_accounts<'info>(
+    pool: &BettingPool,
+    user: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<(AccountInfo<'info>, AccountInfo<'info>)>> {
+    require!(
+        pool.outcomes_ready() && accounts.len() == 2 * pool.outcomes.len(),
+        BettingPoolError::InvalidOutcomeAccounts
+    );
+    
+    let mut pairs = Vec::with_capacity(pool.outcomes.len());
+    for (outcome, chunk) in pool.outcomes.iter().zip(accounts.chunks(2)) {
+        let (mint, token) = (&chunk[0], &chunk[1]);
+        require_keys_eq!(mint.key(), outcome.mint, BettingPoolError::InvalidOutcomeAccounts);
+        
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(token)?;
+        require_keys_eq!(token_account.mint, outcome.mint, BettingPoolError::InvalidOutcomeAccounts);
+        require_keys_eq!(token_account.owner, *user, BettingPoolError::InvalidOutcomeAccounts);
+        
+        pairs.push((mint.clone(), token.clone()));
+    }
+
+    Ok(pairs)
 }
 
 impl BettingPool {
-    pub fn space() -> usize {
-        8 +  // discriminator
-        32 + // authority: Pubkey
-        32 + // yes_mint: Pubkey
-        32 + // no_mint: Pubkey
-        8 +  // total_yes_tokens: u64
-        8 +  // total_no_tokens: u64
-        1 +  // solution_proposed: bool
-        1 + 1 + // solution_winner: Option<bool>
-        8 +  // dispute_period_start: i64
-        8 +  // dispute_period_end: i64
-        8 +  // dispute_period_seconds: i64
-        8 +  // dispute_threshold: u64
-        1 +  // is_disputed: bool
-        1 +  // is_finalized: bool
-        1 +  // bump: u8
-        4 + 32 + // name: String (max 32 chars)
-        4 + 256 + // description: String (max 256 chars)
-        8 +  // end_time: i64
-        8 +  // created_at: i64
-        1 + 32  // disputer: Option<Pubkey>
+    /// Bytes kept free at the end of the account so new fields fit without a realloc.
+    /// Fields added within a version are carved from here, zero being their default.
+    pub const RESERVED_SPACE: usize = 48;
+    
+    /// Whether every outcome mint has been created and betting can open
+    pub fn outcomes_ready(&self) -> bool {
+        self.outcomes.len() == self.outcome_count as usize
+    }
+
+    pub fn outcome(&self, index: u8) -> Result<&Outcome> {
+        self.outcomes
+            .get(index as usize)
+            .ok_or_else(|| error!(BettingPoolError::InvalidOutcome))
+    }
+
+    pub fn outcome_mut(&mut self, index: u8) -> Result<&mut Outcome> {
+        self.outcomes
+            .get_mut(index as usize)
+            .ok_or_else(|| error!(BettingPoolError::InvalidOutcome))
+    }
+
+    pub fn outcome_index(&self, mint: &Pubkey) -> Option<u8> {
+        self.outcomes
+            .iter()
+            .position(|outcome| outcome.mint == *mint)
+            .map(|index| index as u8)
+    }
+
+    /// Whether the authority can propose the answer at `now`: always in a manual
+    /// pool, and in an oracle pool once nobody has resolved it for a long time
+    pub fn accepts_manual_proposal(&self, now: i64) -> Result<bool> {
+        match self.resolution_source {
+            ResolutionSource::Manual => Ok(true),
+            ResolutionSource::Oracle { .. } => {
+                let timeout = self
+                    .end_time
+                    .checked_add(ORACLE_RESOLUTION_TIMEOUT_SECONDS)
+                    .ok_or(BettingPoolError::MathOverflow)?;
+                Ok(now >= timeout)
+            }
+        }
+    }
+
+    /// Whether `outcome` can be proposed or voted as the answer of a categorical market
+    pub fn is_valid_answer(&self, outcome: u8) -> bool {
+        outcome < self.outcome_count || outcome == INVALID_OUTCOME
+    }
+
+    /// Whether holders of `index` can claim: the winning outcome of a categorical
+    /// market, or either side of a scalar market
+    pub fn is_claimable_outcome(&self, index: u8) -> bool {
+        match self.market_kind {
+            MarketKind::Categorical if self.solution_winner == Some(INVALID_OUTCOME) => {
+                (index as usize) < self.outcomes.len()
+            }
+            MarketKind::Categorical => self.solution_winner == Some(index),
+            MarketKind::Scalar { .. } => (index as usize) < self.outcomes.len(),
+        }
+    }
+
+    pub fn is_claimable_mint(&self, mint: &Pubkey) -> bool {
+        self.outcome_index(mint)
+            .is_some_and(|index| self.is_claimable_outcome(index))
+    }
+
+    /// Back `sets` new complete sets, one token of every outcome each, with as much
+    /// collateral
+    pub fn add_complete_sets(&mut self, sets: u64) -> Result<()> {
+        self.total_collateral = self
+            .total_collateral
+            .checked_add(sets)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        self.complete_sets = self
+            .complete_sets
+            .checked_add(sets)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        for outcome in self.outcomes.iter_mut() {
+            outcome.total_tokens = outcome
+                .total_tokens
+                .checked_add(sets)
+                .ok_or(BettingPoolError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_complete_sets(&mut self, sets: u64) -> Result<()> {
+        self.total_collateral = self
+            .total_collateral
+            .checked_sub(sets)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        self.complete_sets = self
+            .complete_sets
+            .checked_sub(sets)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        for outcome in self.outcomes.iter_mut() {
+            outcome.total_tokens = outcome
+                .total_tokens
+                .checked_sub(sets)
+                .ok_or(BettingPoolError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Split the collateral into each outcome's payout pool once the solution is
+    /// final. A pool holds either parimutuel bets or the complete sets of its market
+    /// maker, never both, so every token of an outcome is worth the same share.
+    ///
+    /// The pot goes to the categorical winner, or is split for scalar markets with
+    /// LONG taking the share given by where the value sits between the bounds
+    /// (rounded down) and SHORT the rest. A side nobody holds passes its share to
+    /// the other side. An invalid market refunds what was bet on each outcome and
+    /// splits the complete sets evenly across the outcomes.
+    pub fn settle_payouts(&mut self) -> Result<()> {
+        let pot = self.total_collateral;
+        let complete_sets = self.complete_sets;
+        
+        match self.market_kind {
+            MarketKind::Categorical => {
+                let winner = self.solution_winner.ok_or(BettingPoolError::NoSolutionProposed)?;
+                if winner == INVALID_OUTCOME {
+                    let count = self.outcomes.len() as u128;
+                    for (index, outcome) in self.outcomes.iter_mut().enumerate() {
+                        let bets = outcome
+                            .total_tokens
+                            .checked_sub(complete_sets)
+                            .ok_or(BettingPoolError::MathOverflow)?;
+                        let index = index as u128;
+                        let sets = to_u64(
+                            mul_div(complete_sets as u128, index + 1, count, Rounding::Down)?
+                                - mul_div(complete_sets as u128, index, count, Rounding::Down)?,
+                        )?;
+                        outcome.payout_pool = bets.checked_add(sets).ok_or(BettingPoolError::MathOverflow)?;
+                    }
+                } else {
+                    self.outcome_mut(winner)?.payout_pool = pot;
+                }
+            }
+            MarketKind::Scalar { lower_bound, upper_bound } => {
+                let value = self
+                    .solution_value
+                    .ok_or(BettingPoolError::NoSolutionProposed)?
+                    .clamp(lower_bound, upper_bound);
+                let range = (upper_bound as i128 - lower_bound as i128) as u128;
+                let position = (value as i128 - lower_bound as i128) as u128;
+                let mut long_pool = to_u64(mul_div(pot as u128, position, range, Rounding::Down)?)?;
+                
+                if self.outcome(LONG_OUTCOME)?.total_tokens == 0 {
+                    long_pool = 0;
+                } else if self.outcome(SHORT_OUTCOME)?.total_tokens == 0 {
+                    long_pool = pot;
+                }
+                let short_pool = pot.checked_sub(long_pool).ok_or(BettingPoolError::MathOverflow)?;
+                
+                self.outcome_mut(LONG_OUTCOME)?.payout_pool = long_pool;
+                self.outcome_mut(SHORT_OUTCOME)?.payout_pool = short_pool;
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Whether every paying outcome has been claimed in full
+    pub fn claims_complete(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.payout_pool == 0)
+    }
+
+    /// Tokens needed to open a dispute in the current round
+    pub fn round_dispute_threshold(&self) -> Result<u64> {
+        self.escalated(self.dispute_threshold)
+    }
+
+    /// Bond a disputer posts in the current round
+    pub fn round_dispute_bond(&self) -> Result<u64> {
+        self.escalated(self.dispute_bond)
+    }
+
+    fn escalated(&self, base: u64) -> Result<u64> {
+        DISPUTE_ESCALATION_FACTOR
+            .checked_pow(self.dispute_round as u32)
+            .and_then(|factor| base.checked_mul(factor))
+            .ok_or_else(|| error!(BettingPoolError::MathOverflow))
+    }
+
+    /// Close the current dispute round at `now`. The dispute window reopens for the
+    /// next round, unless this was the last one and the answer is final.
+    pub fn end_dispute_round(&mut self, now: i64) -> Result<()> {
+        self.is_disputed = false;
+        self.dispute_round = self.dispute_round.checked_add(1).ok_or(BettingPoolError::MathOverflow)?;
+        self.dispute_period_start = now;
+        
+        if self.dispute_round < MAX_DISPUTE_ROUNDS {
+            self.dispute_period_end = now
+                .checked_add(self.dispute_period_seconds)
+                .ok_or(BettingPoolError::MathOverflow)?;
+        } else {
+            self.dispute_period_end = now;
+            self.settle_payouts()?;
+            self.is_finalized = true;
+        }
+        
+        Ok(())
+    }
+
+    /// Settle the escrowed bonds once a dispute is resolved and return what the
+    /// winning side is paid. An upheld answer hands the disputer's bond to the
+    /// authority; an overturned one refunds the disputer and adds the proposer's bond.
+    pub fn settle_bonds(&mut self, upheld: bool) -> Result<u64> {
+        let dispute_bond = self.round_dispute_bond()?;
+        if upheld {
+            return Ok(dispute_bond);
+        }
+        
+        let payout = dispute_bond
+            .checked_add(self.proposer_bond)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        self.proposer_bond = 0;
+        Ok(payout)
+    }
+
+    /// Hold back the protocol and creator fees from `payout` and return the rest.
+    /// The fees stay in the vault until their recipients withdraw them.
+    pub fn charge_fees(&mut self, payout: u64) -> Result<u64> {
+        // Refunds of an invalid market are not charged
+        if self.solution_winner == Some(INVALID_OUTCOME) {
+            return Ok(payout);
+        }
+        
+        let protocol_fee = bps_of(payout, self.protocol_fee_bps, Rounding::Down)?;
+        let creator_fee = bps_of(payout, self.creator_fee_bps, Rounding::Down)?;
+        
+        self.protocol_fees = self
+            .protocol_fees
+            .checked_add(protocol_fee)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        self.creator_fees = self
+            .creator_fees
+            .checked_add(creator_fee)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        payout
+            .checked_sub(protocol_fee)
+            .and_then(|rest| rest.checked_sub(creator_fee))
+            .ok_or_else(|| error!(BettingPoolError::MathOverflow))
+    }
+
+    /// Pay out `amount` tokens of `outcome` and remove them and their collateral
+    /// from the pot
+    pub fn take_payout(&mut self, outcome: u8, amount: u64) -> Result<u64> {
+        let state = self.outcome_mut(outcome)?;
+        
+        // Each claim is the same fraction of the remaining pool as of the remaining
+        // supply, so the order of claims does not matter and the last claimer always
+        // receives whatever is left
+        let payout = share_of(amount, state.payout_pool, state.total_tokens)?;
+        state.total_tokens = state
+            .total_tokens
+            .checked_sub(amount)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        state.payout_pool = state
+            .payout_pool
+            .checked_sub(payout)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        self.total_collateral = self
+            .total_collateral
+            .checked_sub(payout)
+            .ok_or(BettingPoolError::MathOverflow)?;
+        
+        Ok(payout)
+    }
+}
+
+/// `amount` of `supply` tokens' share of `pool`, rounded down
+fn share_of(amount: u64, pool: u64, supply: u64) -> Result<u64> {
+    if amount == 0 {
+        return Ok(0);
     }
+    mul_div_u64(amount, pool, supply, Rounding::Down)
 }
 
 #[error_code]
@@ -607,4 +4073,136 @@ pub enum BettingPoolError {
     BettingPeriodNotEnded,
     #[msg("User holds no winning tokens")]
     NoWinningTokens,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Outcome count must be between 2 and MAX_OUTCOMES")]
+    InvalidOutcomeCount,
+    #[msg("Invalid outcome index")]
+    InvalidOutcome,
+    #[msg("Outcome label is too long")]
+    OutcomeLabelTooLong,
+    #[msg("Outcome metadata URI is too long")]
+    OutcomeUriTooLong,
+    #[msg("All outcomes have already been initialized")]
+    OutcomesAlreadyInitialized,
+    #[msg("Not every outcome has been initialized yet")]
+    OutcomesNotInitialized,
+    #[msg("Scalar lower bound must be below the upper bound")]
+    InvalidScalarBounds,
+    #[msg("Instruction does not apply to this market kind")]
+    InvalidMarketKind,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Only two-outcome markets can have a market maker")]
+    AmmRequiresBinaryMarket,
+    #[msg("The market maker has already been funded")]
+    AmmAlreadyFunded,
+    #[msg("The market maker has not been funded")]
+    AmmNotFunded,
+    #[msg("This pool trades through its market maker")]
+    AmmActive,
+    #[msg("Price moved beyond the slippage limit")]
+    SlippageExceeded,
+    #[msg("Not enough liquidity for this trade")]
+    InsufficientLiquidity,
+    #[msg("Outcome accounts must list every outcome mint and the user's token account in order")]
+    InvalidOutcomeAccounts,
+    #[msg("Not enough of the caller's own stake behind this outcome")]
+    InsufficientCollateral,
+    #[msg("This pool is resolved by its oracle until the resolution timeout")]
+    ManualResolutionDisabled,
+    #[msg("This pool is not resolved by an oracle")]
+    NotOracleResolved,
+    #[msg("Oracle account does not match the pool's resolution source")]
+    InvalidOracleAccount,
+    #[msg("Oracle price was published before betting ended")]
+    OraclePriceTooEarly,
+    #[msg("Oracle price was published after the pool's staleness window")]
+    StaleOraclePrice,
+    #[msg("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    #[msg("Bonds have already been configured")]
+    BondsAlreadyConfigured,
+    #[msg("Bond account does not belong to the disputer")]
+    InvalidBondAccount,
+    #[msg("No proposer bond left to withdraw")]
+    NoBondToWithdraw,
+    #[msg("Invalid jury configuration")]
+    InvalidJuryConfig,
+    #[msg("The jury registry is full")]
+    JuryRegistryFull,
+    #[msg("Signer is not a juror here")]
+    NotAJuror,
+    #[msg("Juror stake is locked until its panels are tallied")]
+    JurorStakeLocked,
+    #[msg("Not enough stake to withdraw")]
+    InsufficientStake,
+    #[msg("Not enough eligible jurors to draw a panel")]
+    NotEnoughJurors,
+    #[msg("Disputes on this pool are resolved by a jury")]
+    JuryResolutionEnabled,
+    #[msg("Disputes on this pool are resolved by the authority")]
+    JuryResolutionDisabled,
+    #[msg("Commit period has ended")]
+    CommitPeriodEnded,
+    #[msg("Commit period has not ended yet")]
+    CommitPeriodNotEnded,
+    #[msg("Reveal period has ended")]
+    RevealPeriodEnded,
+    #[msg("Reveal period has not ended yet")]
+    RevealPeriodNotEnded,
+    #[msg("Revealed vote does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Fees exceed the maximum")]
+    FeeTooHigh,
+    #[msg("Fees cannot change once the pool holds collateral")]
+    FeesLocked,
+    #[msg("No fees to withdraw")]
+    NoFeesToWithdraw,
+    #[msg("Dispute period is outside the configured bounds")]
+    InvalidDisputePeriod,
+    #[msg("Collateral mint is not allowed")]
+    CollateralMintNotAllowed,
+    #[msg("Too many allowed collateral mints")]
+    TooManyCollateralMints,
+    #[msg("Pool creation is paused")]
+    PoolCreationPaused,
+    #[msg("This pool is paused")]
+    PoolPaused,
+    #[msg("The protocol is paused")]
+    ProtocolPaused,
+    #[msg("Nothing is paused")]
+    NotPaused,
+    #[msg("Unpause was not requested or its delay has not passed")]
+    UnpauseTimelocked,
+    #[msg("Unpause delay cannot be negative")]
+    InvalidUnpauseDelay,
+    #[msg("Winnings are still unclaimed and the grace period has not passed")]
+    ClaimsOutstanding,
+    #[msg("The transfer hook can only be enabled before outcomes are created")]
+    TransferHookLocked,
+    #[msg("Transfer hook accounts are missing or wrong")]
+    MissingTransferHookAccounts,
+    #[msg("Betting must end in the future")]
+    EndTimeInPast,
+    #[msg("Pool name is too long")]
+    PoolNameTooLong,
+    #[msg("Pool description is too long")]
+    PoolDescriptionTooLong,
+    #[msg("Pool already uses the current layout")]
+    PoolAlreadyMigrated,
+    #[msg("Oracle price was published too long after betting ended")]
+    OraclePriceTooLate,
+    #[msg("The slot seeding the jury draw has not been produced yet")]
+    JuryDrawTooEarly,
+    #[msg("Complete sets are only available in pools with a market maker")]
+    CompleteSetsRequireAmm,
+    #[msg("The market maker can only be funded before any bet is placed")]
+    PoolHasBets,
+    #[msg("Winnings of this outcome were already claimed")]
+    AlreadyClaimed,
+    #[msg("Pools created before versioning hold no collateral and cannot be migrated")]
+    LegacyPoolUnsupported,
 }
@@ -0,0 +1,303 @@
+use anchor_lang::prelude::*;
+
+use crate::{BettingPoolError, BPS_DENOMINATOR};
+
+/// Which way a division that does not come out even is rounded. Amounts paid out
+/// of the pool round down and amounts paid into it round up, so rounding never
+/// leaves the pool owing more than it holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// `value * numerator / denominator` with a u128 intermediate, rounded as asked.
+/// Overflow and division by zero are errors rather than panics.
+pub fn mul_div(value: u128, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    require!(denominator != 0, BettingPoolError::MathOverflow);
+    let product = value.checked_mul(numerator).ok_or(BettingPoolError::MathOverflow)?;
+
+    let quotient = product / denominator;
+    match rounding {
+        Rounding::Up if product % denominator != 0 => {
+            Ok(quotient.checked_add(1).ok_or(BettingPoolError::MathOverflow)?)
+        }
+        _ => Ok(quotient),
+    }
+}
+
+/// `mul_div` on token amounts, failing when the result does not fit a u64
+pub fn mul_div_u64(value: u64, numerator: u64, denominator: u64, rounding: Rounding) -> Result<u64> {
+    to_u64(mul_div(value as u128, numerator as u128, denominator as u128, rounding)?)
+}
+
+/// `bps` basis points of `amount`
+pub fn bps_of(amount: u64, bps: u64, rounding: Rounding) -> Result<u64> {
+    mul_div_u64(amount, bps, BPS_DENOMINATOR, rounding)
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(BettingPoolError::MathOverflow))
+}
+
+/// Smallest integer whose square is at least `n`
+pub fn isqrt_ceil(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from a starting point above the root
+    let bits = 128 - n.leading_zeros();
+    let mut root = 1u128 << bits.div_ceil(2);
+    loop {
+        let next = (root + n / root) / 2;
+        if next >= root {
+            break;
+        }
+        root = next;
+    }
+
+    if root * root < n {
+        root + 1
+    } else {
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        BettingPool, MarketKind, Outcome, PauseState, ResolutionSource, INVALID_OUTCOME, LONG_OUTCOME,
+        MAX_FEE_BPS, MAX_OUTCOMES, POOL_VERSION, SHORT_OUTCOME,
+    };
+
+    const CASES: usize = 2_000;
+
+    /// A pool holding `bets[i]` tokens bet on outcome `i`, plus `complete_sets` sets
+    /// backing every outcome. The program keeps one of them at zero.
+    fn pool(market_kind: MarketKind, bets: &[u64], complete_sets: u64) -> BettingPool {
+        BettingPool {
+            authority: Pubkey::default(),
+            operator: Pubkey::default(),
+            pending_operator: None,
+            market_id: 0,
+            collateral_mint: Pubkey::default(),
+            collateral_vault: Pubkey::default(),
+            total_collateral: bets.iter().sum::<u64>() + complete_sets,
+            complete_sets,
+            protocol_fee_bps: 0,
+            creator_fee_bps: 0,
+            protocol_fees: 0,
+            creator_fees: 0,
+            market_kind,
+            resolution_source: ResolutionSource::Manual,
+            outcome_count: bets.len() as u8,
+            outcomes: bets
+                .iter()
+                .map(|tokens| Outcome {
+                    mint: Pubkey::new_unique(),
+                    label: String::new(),
+                    total_tokens: tokens + complete_sets,
+                    payout_pool: 0,
+                })
+                .collect(),
+            solution_proposed: true,
+            solution_winner: None,
+            solution_value: None,
+            dispute_period_start: 0,
+            dispute_period_end: 0,
+            dispute_period_seconds: 0,
+            dispute_threshold: 0,
+            proposer_bond: 0,
+            dispute_bond: 0,
+            jury_resolution: false,
+            transfer_hook: false,
+            dispute_round: 0,
+            is_disputed: false,
+            is_finalized: true,
+            pause: PauseState::default(),
+            bump: 0,
+            name: String::new(),
+            description: String::new(),
+            end_time: 0,
+            created_at: 0,
+            disputer: None,
+            amm: None,
+            version: POOL_VERSION,
+            disputed_at: 0,
+            dispute_slot: 0,
+            reserved: [0; BettingPool::RESERVED_SPACE],
+        }
+    }
+
+    /// Claim every token of every claimable outcome in random chunks and return the
+    /// collateral paid out, checking each claim is worth at least its share of the
+    /// settled pool, whoever claimed before
+    fn claim_everything(rng: &mut StdRng, pool: &mut BettingPool) -> u64 {
+        let mut paid = 0u64;
+        for index in 0..pool.outcomes.len() as u8 {
+            if !pool.is_claimable_outcome(index) {
+                continue;
+            }
+            let settled = pool.outcome(index).unwrap().clone();
+            while pool.outcome(index).unwrap().total_tokens > 0 {
+                let remaining = pool.outcome(index).unwrap().total_tokens;
+                let amount = rng.gen_range(1..=remaining);
+                let before = pool.total_collateral;
+
+                let payout = pool.take_payout(index, amount).unwrap();
+                assert!(payout <= before);
+                let fair = mul_div_u64(amount, settled.payout_pool, settled.total_tokens, Rounding::Down).unwrap();
+                assert!(payout >= fair);
+                paid += payout;
+            }
+            assert_eq!(pool.outcome(index).unwrap().payout_pool, 0);
+        }
+        paid
+    }
+
+    /// A random pool holding either bets or market maker sets
+    fn random_pool(rng: &mut StdRng, market_kind: MarketKind, outcome_count: usize) -> BettingPool {
+        if rng.gen_bool(0.5) {
+            // An outcome nobody bet on leaves its pot to the close-out sweep
+            let bets: Vec<u64> = (0..outcome_count).map(|_| random_tokens(rng).max(1)).collect();
+            pool(market_kind, &bets, 0)
+        } else {
+            pool(market_kind, &vec![0; outcome_count], random_tokens(rng))
+        }
+    }
+
+    fn random_tokens(rng: &mut StdRng) -> u64 {
+        // Small pools hit the rounding edge cases, large ones the overflow checks
+        if rng.gen_bool(0.5) {
+            rng.gen_range(0..1_000)
+        } else {
+            rng.gen_range(0..u64::MAX / (4 * MAX_OUTCOMES as u64))
+        }
+    }
+
+    #[test]
+    fn mul_div_rounds_as_asked() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..CASES {
+            let value = rng.gen::<u64>() as u128;
+            let numerator = rng.gen::<u64>() as u128;
+            let denominator = rng.gen_range(1..=u64::MAX) as u128;
+
+            let down = mul_div(value, numerator, denominator, Rounding::Down).unwrap();
+            let up = mul_div(value, numerator, denominator, Rounding::Up).unwrap();
+            assert!(down * denominator <= value * numerator);
+            assert!(value * numerator < (down + 1) * denominator);
+            assert_eq!(up - down, !(value * numerator).is_multiple_of(denominator) as u128);
+        }
+
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+        assert!(mul_div(u128::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div_u64(u64::MAX, 2, 1, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn isqrt_ceil_is_the_smallest_root() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..CASES {
+            let n = rng.gen::<u128>() >> rng.gen_range(2..128);
+            let root = isqrt_ceil(n);
+            assert!(root * root >= n);
+            assert!(root == 0 || (root - 1) * (root - 1) < n);
+        }
+    }
+
+    #[test]
+    fn categorical_payouts_never_exceed_the_collateral() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..CASES {
+            let outcome_count = rng.gen_range(2..=MAX_OUTCOMES);
+            let mut pool = random_pool(&mut rng, MarketKind::Categorical, outcome_count);
+            let total_collateral = pool.total_collateral;
+
+            pool.solution_winner = Some(if rng.gen_bool(0.2) {
+                INVALID_OUTCOME
+            } else {
+                rng.gen_range(0..outcome_count as u8)
+            });
+            pool.settle_payouts().unwrap();
+            let settled: u64 = pool
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.payout_pool)
+                .sum();
+            assert!(settled <= total_collateral);
+
+            let paid = claim_everything(&mut rng, &mut pool);
+            assert!(paid <= total_collateral);
+            assert_eq!(paid, settled, "Last claims sweep the rounding dust");
+        }
+    }
+
+    #[test]
+    fn scalar_payouts_never_exceed_the_collateral() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..CASES {
+            let lower_bound = rng.gen_range(i64::MIN..i64::MAX);
+            let upper_bound = rng.gen_range(lower_bound + 1..=i64::MAX);
+            let mut pool = random_pool(&mut rng, MarketKind::Scalar { lower_bound, upper_bound }, 2);
+            let total_collateral = pool.total_collateral;
+
+            pool.solution_value = Some(rng.gen());
+            pool.settle_payouts().unwrap();
+            let settled: u64 = pool
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.payout_pool)
+                .sum();
+            assert!(settled <= total_collateral);
+            if pool.complete_sets > 0 {
+                let long = pool.outcome(LONG_OUTCOME).unwrap().payout_pool;
+                let short = pool.outcome(SHORT_OUTCOME).unwrap().payout_pool;
+                assert_eq!(long + short, pool.complete_sets, "Each set is worth exactly one unit");
+            }
+
+            assert!(claim_everything(&mut rng, &mut pool) <= total_collateral);
+        }
+    }
+
+    #[test]
+    fn market_maker_tokens_are_paid_at_par_in_any_order() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..CASES {
+            let outcome_count = rng.gen_range(2..=MAX_OUTCOMES);
+            let complete_sets = random_tokens(&mut rng).max(1);
+            let winner = rng.gen_range(0..outcome_count as u8);
+            let mut pool = pool(MarketKind::Categorical, &vec![0; outcome_count], complete_sets);
+            pool.solution_winner = Some(winner);
+            pool.settle_payouts().unwrap();
+
+            // Whoever claims first, each winning token is worth exactly one unit
+            let mut remaining = complete_sets;
+            while remaining > 0 {
+                let amount = rng.gen_range(1..=remaining);
+                assert_eq!(pool.take_payout(winner, amount).unwrap(), amount);
+                remaining -= amount;
+            }
+            assert_eq!(pool.total_collateral, 0);
+        }
+    }
+
+    #[test]
+    fn fees_never_exceed_the_payout() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..CASES {
+            let mut pool = pool(MarketKind::Categorical, &[1, 1], 0);
+            pool.solution_winner = Some(0);
+            pool.protocol_fee_bps = rng.gen_range(0..=MAX_FEE_BPS);
+            pool.creator_fee_bps = rng.gen_range(0..=MAX_FEE_BPS);
+
+            let payout = rng.gen::<u64>();
+            let net = pool.charge_fees(payout).unwrap();
+            assert_eq!(net + pool.protocol_fees + pool.creator_fees, payout);
+        }
+    }
+}
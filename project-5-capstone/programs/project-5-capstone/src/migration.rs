@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// The `BettingPool` layout of the program before pools were versioned, only kept
+/// to recognise its accounts. Those pools sit at `[b"pool", authority]` rather than
+/// a market id seed, and their YES/NO tokens were minted for free with no
+/// collateral behind them. There is nothing a claim could pay out of, so they are
+/// not supported: `migrate_pool` rejects them rather than carry the tokens over.
+#[derive(InitSpace)]
+pub struct LegacyBettingPool {
+    pub authority: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+    pub total_yes_tokens: u64,
+    pub total_no_tokens: u64,
+    pub solution_proposed: bool,
+    pub solution_winner: Option<bool>,
+    pub dispute_period_start: i64,
+    pub dispute_period_end: i64,
+    pub dispute_period_seconds: i64,
+    pub dispute_threshold: u64,
+    pub is_disputed: bool,
+    pub is_finalized: bool,
+    pub bump: u8,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(256)]
+    pub description: String,
+    pub end_time: i64,
+    pub created_at: i64,
+    pub disputer: Option<Pubkey>,
+}
+
+impl LegacyBettingPool {
+    /// Size of every pool account the old program created
+    pub const SPACE: usize = 8 + Self::INIT_SPACE;
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::BettingPoolError;
+
+/// Program that owns Pyth `PriceUpdateV2` accounts on every cluster
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// Switchboard on-demand program on mainnet
+pub const SWITCHBOARD_MAINNET_PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+/// Switchboard on-demand program on devnet
+pub const SWITCHBOARD_DEVNET_PROGRAM_ID: Pubkey = pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+
+/// Decimal places of every Switchboard on-demand value
+pub const SWITCHBOARD_DECIMALS: u8 = 18;
+
+// sha256("account:PriceUpdateV2")[..8]
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+// sha256("account:PullFeedAccountData")[..8]
+const PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+// Offsets into PullFeedAccountData after its discriminator. The struct is repr(C):
+// 32 submissions of 64 bytes, then the feed settings, then `result` at 2256 and
+// `submission_timestamps` at 2944.
+const PULL_FEED_RESULT_OFFSET: usize = 2256;
+const PULL_FEED_RESULT_SIZE: usize = 128;
+const PULL_FEED_TIMESTAMPS_OFFSET: usize = 2944;
+
+/// An oracle reading in the units the pool's strike is written in
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64, // confidence interval or standard deviation, same units as price
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Read a fully verified Pyth `PriceUpdateV2` account. The layout is
+    /// discriminator, write authority, verification level, then the price message.
+    /// Prices keep the feed's own exponent.
+    pub fn from_pyth(account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*account.owner, PYTH_RECEIVER_PROGRAM_ID, BettingPoolError::InvalidOracleAccount);
+
+        let data = account.try_borrow_data()?;
+        let mut reader = Reader::new(&data);
+        require!(
+            reader.take::<8>()? == PRICE_UPDATE_V2_DISCRIMINATOR,
+            BettingPoolError::InvalidOracleAccount
+        );
+        reader.skip(32)?; // write_authority
+
+        // VerificationLevel::Partial { num_signatures } is 0, VerificationLevel::Full is 1
+        let [verification_level] = reader.take::<1>()?;
+        require!(verification_level == 1, BettingPoolError::InvalidOracleAccount);
+
+        reader.skip(32)?; // feed_id
+        let price = i64::from_le_bytes(reader.take()?);
+        let conf = u64::from_le_bytes(reader.take()?);
+        reader.skip(4)?; // exponent
+        let publish_time = i64::from_le_bytes(reader.take()?);
+
+        Ok(OraclePrice {
+            price,
+            conf,
+            publish_time,
+        })
+    }
+
+    /// Read the current result of a Switchboard on-demand pull feed, rescaled from
+    /// 18 decimals to `decimals`. The standard deviation of the oracle submissions
+    /// stands in for the confidence interval.
+    pub fn from_switchboard(account: &AccountInfo, decimals: u8) -> Result<Self> {
+        require!(
+            *account.owner == SWITCHBOARD_MAINNET_PROGRAM_ID
+                || *account.owner == SWITCHBOARD_DEVNET_PROGRAM_ID,
+            BettingPoolError::InvalidOracleAccount
+        );
+
+        let data = account.try_borrow_data()?;
+        let mut reader = Reader::new(&data);
+        require!(
+            reader.take::<8>()? == PULL_FEED_DISCRIMINATOR,
+            BettingPoolError::InvalidOracleAccount
+        );
+
+        // result: value, std_dev, mean, range, min_value, max_value, num_samples,
+        // submission_idx, padding, slot, min_slot, max_slot
+        reader.skip(PULL_FEED_RESULT_OFFSET)?;
+        let value = i128::from_le_bytes(reader.take()?);
+        let std_dev = i128::from_le_bytes(reader.take()?);
+        reader.skip(4 * 16 + 1)?;
+        let [submission_idx] = reader.take::<1>()?;
+        reader.skip(6)?;
+        let slot = u64::from_le_bytes(reader.take()?);
+        require!(slot != 0 && submission_idx < 32, BettingPoolError::InvalidOracleAccount);
+
+        // The result's timestamp is the one its submission landed with
+        reader.skip(
+            PULL_FEED_TIMESTAMPS_OFFSET - PULL_FEED_RESULT_OFFSET - PULL_FEED_RESULT_SIZE
+                + submission_idx as usize * 8,
+        )?;
+        let publish_time = i64::from_le_bytes(reader.take()?);
+
+        let scale = 10i128.pow(
+            SWITCHBOARD_DECIMALS
+                .checked_sub(decimals)
+                .ok_or(BettingPoolError::InvalidOracleConfig)? as u32,
+        );
+        Ok(OraclePrice {
+            price: i64::try_from(value / scale).map_err(|_| error!(BettingPoolError::MathOverflow))?,
+            conf: u64::try_from(std_dev.unsigned_abs() / scale as u128)
+                .map_err(|_| error!(BettingPoolError::MathOverflow))?,
+            publish_time,
+        })
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        require!(self.data.len() >= N, BettingPoolError::InvalidOracleAccount);
+        let (head, rest) = self.data.split_at(N);
+        self.data = rest;
+        head.try_into().map_err(|_| error!(BettingPoolError::InvalidOracleAccount))
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        require!(self.data.len() >= len, BettingPoolError::InvalidOracleAccount);
+        self.data = &self.data[len..];
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::{BettingPoolError, MAX_OUTCOMES};
+
+/// A user's activity in one pool. It is opened by the user's first trade and lets
+/// frontends show cost basis and PnL without replaying history. Tokens moved
+/// between wallets are not tracked, only what the pool minted, burned and paid.
+#[account]
+pub struct Position {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub deposited: u64, // collateral paid in by mints, complete sets and market maker buys
+    pub withdrawn: u64, // collateral paid back by burns, redemptions and market maker sells
+    pub minted: [u64; MAX_OUTCOMES], // tokens received per outcome
+    pub burned: [u64; MAX_OUTCOMES], // tokens handed back per outcome outside of claims
+    pub claimed: u64, // collateral paid out by claims, after fees
+    pub claimed_outcomes: u8, // one bit per outcome whose winnings were claimed
+    pub bump: u8,
+}
+
+impl Position {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // pool: Pubkey
+        32 + // user: Pubkey
+        8 +  // deposited: u64
+        8 +  // withdrawn: u64
+        8 * MAX_OUTCOMES + // minted: [u64; MAX_OUTCOMES]
+        8 * MAX_OUTCOMES + // burned: [u64; MAX_OUTCOMES]
+        8 +  // claimed: u64
+        1 +  // claimed_outcomes: u8
+        1; // bump: u8
+
+    /// Fill in the owner of a position created by `init_if_needed`
+    pub fn open(&mut self, pool: Pubkey, user: Pubkey, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.pool = pool;
+            self.user = user;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_deposit(&mut self, collateral: u64) -> Result<()> {
+        self.deposited = self.deposited.checked_add(collateral).ok_or(BettingPoolError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, collateral: u64) -> Result<()> {
+        self.withdrawn = self.withdrawn.checked_add(collateral).ok_or(BettingPoolError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_mint(&mut self, outcome: u8, tokens: u64) -> Result<()> {
+        let minted = self.minted.get_mut(outcome as usize).ok_or(BettingPoolError::InvalidOutcome)?;
+        *minted = minted.checked_add(tokens).ok_or(BettingPoolError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_burn(&mut self, outcome: u8, tokens: u64) -> Result<()> {
+        let burned = self.burned.get_mut(outcome as usize).ok_or(BettingPoolError::InvalidOutcome)?;
+        *burned = burned.checked_add(tokens).ok_or(BettingPoolError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Tokens of `outcome` the user bet and has not handed back, the only ones a
+    /// burn can refund 1:1
+    pub fn single_sided(&self, outcome: u8) -> Result<u64> {
+        let index = outcome as usize;
+        require!(index < MAX_OUTCOMES, BettingPoolError::InvalidOutcome);
+        Ok(self.minted[index].saturating_sub(self.burned[index]))
+    }
+
+    /// Record the claim of `outcome`'s winnings. Each outcome pays a position once,
+    /// so the whole balance has to be claimed together rather than piece by piece.
+    pub fn record_claim(&mut self, outcome: u8, payout: u64) -> Result<()> {
+        require!((outcome as usize) < MAX_OUTCOMES, BettingPoolError::InvalidOutcome);
+        let bit = 1u8 << outcome;
+        require!(self.claimed_outcomes & bit == 0, BettingPoolError::AlreadyClaimed);
+        
+        self.claimed_outcomes |= bit;
+        self.claimed = self.claimed.checked_add(payout).ok_or(BettingPoolError::MathOverflow)?;
+        Ok(())
+    }
+}
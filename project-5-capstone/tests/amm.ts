@@ -0,0 +1,228 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("amm", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const trader = Keypair.generate();
+
+  const marketId = new anchor.BN(21);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const traderYes = getAssociatedTokenAddressSync(yesMint, trader.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let traderCollateral: PublicKey;
+  let endTime: number;
+
+  const tradeAccounts = () => ({
+    user: trader.publicKey,
+    pool: poolPda,
+    config: findConfigPda(program.programId),
+    collateralMint,
+    collateralVault,
+    userCollateral: traderCollateral,
+    outcomeMint: yesMint,
+    userOutcomeToken: traderYes,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    collateralTokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  const balance = async (account: PublicKey, programId = TOKEN_PROGRAM_ID) =>
+    (await getAccount(provider.connection, account, "confirmed", programId)).amount;
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up collateral and users", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, trader.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    traderCollateral = await createAssociatedTokenAccount(provider.connection, trader, collateralMint, trader.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, authorityCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, traderCollateral, authority, 10_000_000);
+  });
+
+  it("Initialize a binary pool and fund its market maker", async () => {
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "AMM pool",
+      description: "Priced by a constant-product market maker",
+    });
+    
+    await program.methods
+      .fundAmm(new anchor.BN(10_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        collateralVault,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.deepEqual(poolData.amm.reserves.map((reserve) => reserve.toString()), ["10000000", "10000000"]);
+    assert.equal(poolData.amm.impliedProbabilityBps.toNumber(), 5000);
+  });
+
+  it("1:1 minting is disabled once the market maker is funded", async () => {
+    try {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(1_000_000), YES)
+        .accounts({ ...tradeAccounts(), rent: anchor.web3.SYSVAR_RENT_PUBKEY })
+        .signers([trader])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Minting should go through the market maker");
+    } catch (error) {
+      assert.include(error.toString(), "AmmActive");
+    }
+  });
+
+  it("Rejects a buy above max_cost", async () => {
+    try {
+      await program.methods
+        .buyOutcomeTokens(YES, new anchor.BN(1_000_000), new anchor.BN(500_000))
+        .accounts(tradeAccounts())
+        .signers([trader])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Buy should exceed the slippage limit");
+    } catch (error) {
+      assert.include(error.toString(), "SlippageExceeded");
+    }
+  });
+
+  it("Buying YES raises its implied probability", async () => {
+    const before = await balance(traderCollateral);
+    
+    await program.methods
+      .buyOutcomeTokens(YES, new anchor.BN(1_000_000), new anchor.BN(520_000))
+      .accounts(tradeAccounts())
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+    
+    // (10M + cost - 1M) * (10M + cost) >= 10M * 10M gives a cost of 512,493
+    assert.equal((before - (await balance(traderCollateral))).toString(), "512493");
+    assert.equal((await balance(traderYes, TOKEN_2022_PROGRAM_ID)).toString(), "1000000");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.deepEqual(poolData.amm.reserves.map((reserve) => reserve.toString()), ["9512493", "10512493"]);
+    assert.equal(poolData.amm.impliedProbabilityBps.toNumber(), 5249);
+  });
+
+  it("Selling YES back pays less than it cost", async () => {
+    const before = await balance(traderCollateral);
+    
+    await program.methods
+      .sellOutcomeTokens(YES, new anchor.BN(500_000), new anchor.BN(250_000))
+      .accounts(tradeAccounts())
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal(((await balance(traderCollateral)) - before).toString(), "259367");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.deepEqual(poolData.amm.reserves.map((reserve) => reserve.toString()), ["9753126", "10253126"]);
+    assert.isBelow(poolData.amm.impliedProbabilityBps.toNumber(), 5249);
+  });
+
+  it("Trader and market maker claim the whole vault after YES wins", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const proposed = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, proposed.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const traderBefore = await balance(traderCollateral);
+    await program.methods
+      .claimWinnings()
+      .accounts({
+        user: trader.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        userCollateral: traderCollateral,
+        winningMint: yesMint,
+        userWinningToken: traderYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+    assert.equal(((await balance(traderCollateral)) - traderBefore).toString(), "500000");
+    
+    const authorityBefore = await balance(authorityCollateral);
+    await program.methods
+      .claimAmmLiquidity()
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    assert.equal(((await balance(authorityCollateral)) - authorityBefore).toString(), "9753126");
+    
+    assert.equal((await balance(collateralVault)).toString(), "0", "Vault should be empty after all claims");
+  });
+});
@@ -0,0 +1,127 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  createMint,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  findPoolPda,
+  initializePool,
+  ensureConfig,
+} from "./helpers";
+
+describe("authority transfer", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+  const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+  const authority = Keypair.generate();
+  const operator = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  const marketId = new anchor.BN(121);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+
+  let collateralMint: PublicKey;
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      assert.fail(`Expected ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  };
+
+  const proposeAuthority = (signer: Keypair, newOperator: PublicKey) =>
+    program.methods
+      .proposeAuthority(newOperator)
+      .accounts({ authority: signer.publicKey, pool: poolPda })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  
+  const acceptAuthority = (signer: Keypair) =>
+    program.methods
+      .acceptAuthority()
+      .accounts({ newOperator: signer.publicKey, pool: poolPda })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  
+  const configureCreatorFee = (signer: Keypair, bps: number) =>
+    program.methods
+      .configureCreatorFee(new anchor.BN(bps))
+      .accounts({ authority: signer.publicKey, pool: poolPda })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a pool operated by its creator", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, operator.publicKey, 1);
+    await airdrop(provider.connection, stranger.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    await initializePool(program, authority, marketId, collateralMint, {
+      endTime: Math.floor(Date.now() / 1000) + 300,
+      disputePeriodSeconds: 60,
+      name: "Handover pool",
+      description: "Run by a new operator after creation",
+    });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.ok(poolData.operator.equals(authority.publicKey));
+    assert.isNull(poolData.pendingOperator);
+  });
+
+  it("Only the operator can propose a new one", async () => {
+    await expectError(proposeAuthority(stranger, stranger.publicKey), "Unauthorized");
+  });
+
+  it("Only the proposed key can accept", async () => {
+    await proposeAuthority(authority, operator.publicKey);
+    await expectError(acceptAuthority(stranger), "Unauthorized");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.ok(poolData.pendingOperator.equals(operator.publicKey));
+    assert.ok(poolData.operator.equals(authority.publicKey), "Nothing changes until it is accepted");
+  });
+
+  it("The new operator takes over at the same pool address", async () => {
+    const signature = await acceptAuthority(operator);
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [...parser.parseLogs(tx.meta.logMessages)].filter((event) => event.name === "authorityTransferred");
+    assert.lengthOf(events, 1);
+    assert.ok(events[0].data.previousOperator.equals(authority.publicKey));
+    assert.ok(events[0].data.newOperator.equals(operator.publicKey));
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.ok(poolData.authority.equals(authority.publicKey));
+    assert.ok(poolData.operator.equals(operator.publicKey));
+    assert.isNull(poolData.pendingOperator);
+    
+    await expectError(configureCreatorFee(authority, 100), "Unauthorized");
+    await configureCreatorFee(operator, 100);
+    assert.equal((await program.account.bettingPool.fetch(poolPda)).creatorFeeBps.toNumber(), 100);
+  });
+
+  it("Proposing the current operator cancels a pending handover", async () => {
+    await proposeAuthority(operator, stranger.publicKey);
+    await proposeAuthority(operator, operator.publicKey);
+    
+    assert.isNull((await program.account.bettingPool.fetch(poolPda)).pendingOperator);
+    await expectError(acceptAuthority(stranger), "Unauthorized");
+  });
+});
@@ -0,0 +1,205 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("bonded disputes", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const disputer = Keypair.generate();
+
+  const marketId = new anchor.BN(51);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const disputerNo = getAssociatedTokenAddressSync(noMint, disputer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let disputerCollateral: PublicKey;
+  let endTime: number;
+
+  const balance = async (account: PublicKey) => (await getAccount(provider.connection, account)).amount;
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a bonded pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, disputer.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    disputerCollateral = await createAssociatedTokenAccount(provider.connection, disputer, collateralMint, disputer.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, authorityCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, disputerCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Bonded pool",
+      description: "Disputes cost a bond",
+    });
+    
+    await program.methods
+      .configureBonds(new anchor.BN(1_000_000), new anchor.BN(2_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .mintPredictionTokens(new anchor.BN(1_000_000), NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        userCollateral: disputerCollateral,
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Bonds cannot be reconfigured", async () => {
+    try {
+      await program.methods
+        .configureBonds(new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          authority: authority.publicKey,
+          pool: poolPda,
+          collateralMint,
+          bondEscrow,
+          authorityCollateral,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Bonds should only be configured once");
+    } catch (error) {
+      assert.include(error.toString(), "BondsAlreadyConfigured");
+    }
+  });
+
+  it("A frivolous dispute forfeits the bond to the proposer", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .disputeSolution(NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        collateralMint,
+        bondEscrow,
+        userCollateral: disputerCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+    assert.equal((await balance(disputerCollateral)).toString(), "7000000");
+    
+    const authorityBefore = await balance(authorityCollateral);
+    await program.methods
+      .resolveDispute(YES)
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        disputerCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal(((await balance(authorityCollateral)) - authorityBefore).toString(), "2000000");
+    assert.equal((await balance(disputerCollateral)).toString(), "7000000", "The disputer's bond is slashed");
+  });
+
+  it("The authority withdraws its proposer bond after finalization", async () => {
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, poolData.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const authorityBefore = await balance(authorityCollateral);
+    await program.methods
+      .withdrawProposerBond()
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal(((await balance(authorityCollateral)) - authorityBefore).toString(), "1000000");
+    assert.equal((await balance(bondEscrow)).toString(), "0", "Escrow should be empty");
+  });
+});
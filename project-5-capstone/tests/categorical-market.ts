@@ -0,0 +1,197 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  initializePool,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+describe("categorical market", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const users = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const labels = ["RED", "GREEN", "BLUE"];
+  const stakes = [2_000_000, 3_000_000, 5_000_000];
+
+  const marketId = new anchor.BN(7);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const outcomeMints = labels.map((_, index) => findOutcomeMintPda(program.programId, poolPda, index));
+
+  let collateralMint: PublicKey;
+  let userCollateral: PublicKey[] = [];
+  let endTime: number;
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up collateral and users", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    for (const user of users) {
+      await airdrop(provider.connection, user.publicKey, 1);
+    }
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    for (const user of users) {
+      const account = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+      await mintTo(provider.connection, authority, collateralMint, account, authority, 10_000_000);
+      userCollateral.push(account);
+    }
+  });
+
+  it("Rejects a pool with a single outcome", async () => {
+    const badPool = findPoolPda(program.programId, authority.publicKey, new anchor.BN(8));
+    try {
+      await initializePool(program, authority, new anchor.BN(8), collateralMint, {
+        endTime: Math.floor(Date.now() / 1000) + 60,
+        outcomeCount: 1,
+        name: "Bad pool",
+        description: "Only one outcome",
+      });
+      assert.fail("Pool with one outcome should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidOutcomeCount");
+    }
+  });
+
+  it("Initialize a three-outcome pool", async () => {
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Color of the year",
+      description: "Which color will be picked?",
+      labels,
+    });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.deepEqual(poolData.outcomes.map((outcome) => outcome.label), labels);
+  });
+
+  it("Each user bets on a different outcome", async () => {
+    for (let index = 0; index < users.length; index++) {
+      const user = users[index];
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(stakes[index]), index)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral: userCollateral[index],
+          outcomeMint: outcomeMints[index],
+          userOutcomeToken: getAssociatedTokenAddressSync(outcomeMints[index], user.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.totalCollateral.toString(), "10000000");
+    poolData.outcomes.forEach((outcome, index) => {
+      assert.equal(outcome.totalTokens.toString(), stakes[index].toString());
+    });
+  });
+
+  it("GREEN is proposed and finalized", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(1)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const proposed = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, proposed.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("A losing outcome cannot be claimed", async () => {
+    const loser = users[2];
+    try {
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          user: loser.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral: userCollateral[2],
+          winningMint: outcomeMints[2],
+          userWinningToken: getAssociatedTokenAddressSync(outcomeMints[2], loser.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([loser])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Claiming a losing outcome should fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidOutcome");
+    }
+  });
+
+  it("The GREEN holder receives the whole pot", async () => {
+    const winner = users[1];
+    const before = await getAccount(provider.connection, userCollateral[1]);
+    
+    await program.methods
+      .claimWinnings()
+      .accounts({
+        user: winner.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        userCollateral: userCollateral[1],
+        winningMint: outcomeMints[1],
+        userWinningToken: getAssociatedTokenAddressSync(outcomeMints[1], winner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([winner])
+      .rpc({ commitment: "confirmed" });
+    
+    const after = await getAccount(provider.connection, userCollateral[1]);
+    assert.equal((after.amount - before.amount).toString(), "10000000");
+  });
+});
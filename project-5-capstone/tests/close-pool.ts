@@ -0,0 +1,203 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("close pool", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const yesUser = Keypair.generate();
+  const noUser = Keypair.generate();
+
+  const marketId = new anchor.BN(151);
+  const config = findConfigPda(program.programId);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const yesToken = getAssociatedTokenAddressSync(yesMint, yesUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const noToken = getAssociatedTokenAddressSync(noMint, noUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let treasuryCollateral: PublicKey;
+  let yesCollateral: PublicKey;
+  let noCollateral: PublicKey;
+  let endTime: number;
+
+  const balance = async (account: PublicKey) => (await getAccount(provider.connection, account)).amount;
+
+  const closePool = () =>
+    program.methods
+      .closePool()
+      .accounts({
+        authority: authority.publicKey,
+        creator: authority.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        bondEscrow,
+        authorityCollateral,
+        treasuryCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([yesMint, noMint].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up and finalize a pool with a creator fee", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, yesUser.publicKey, 1);
+    await airdrop(provider.connection, noUser.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    yesCollateral = await createAssociatedTokenAccount(provider.connection, yesUser, collateralMint, yesUser.publicKey);
+    noCollateral = await createAssociatedTokenAccount(provider.connection, noUser, collateralMint, noUser.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, yesCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, noCollateral, authority, 10_000_000);
+    
+    const { treasury } = await program.account.globalConfig.fetch(config);
+    treasuryCollateral = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, authority, collateralMint, treasury, true)
+    ).address;
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Closable pool",
+      description: "Its rent goes back to the creator",
+    });
+    
+    await program.methods
+      .configureCreatorFee(new anchor.BN(100))
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    for (const [user, userCollateral, outcome, outcomeMint, userOutcomeToken] of [
+      [yesUser, yesCollateral, YES, yesMint, yesToken],
+      [noUser, noCollateral, NO, noMint, noToken],
+    ] as const) {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(2_000_000), outcome)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config,
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint,
+          userOutcomeToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+    
+    await waitUntil(provider.connection, endTime);
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, poolData.disputePeriodEnd.toNumber());
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("The pool stays open while winnings are unclaimed", async () => {
+    try {
+      await closePool();
+      assert.fail("Unclaimed winnings should block closing");
+    } catch (error) {
+      assert.include(error.toString(), "ClaimsOutstanding");
+    }
+  });
+
+  it("Claiming closes the emptied token account", async () => {
+    await program.methods
+      .claimWinnings()
+      .accounts({
+        user: yesUser.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral: yesCollateral,
+        winningMint: yesMint,
+        userWinningToken: yesToken,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([yesUser])
+      .rpc({ commitment: "confirmed" });
+    
+    // 4M pot less the 1% creator fee
+    assert.equal((await balance(yesCollateral)).toString(), "11960000");
+    assert.isNull(await provider.connection.getAccountInfo(yesToken));
+  });
+
+  it("Closing pays the operator, sweeps the rest and returns the rent", async () => {
+    const creatorBefore = await provider.connection.getBalance(authority.publicKey);
+    await closePool();
+    
+    assert.equal((await balance(authorityCollateral)).toString(), "40000", "The operator gets its creator fees");
+    for (const account of [poolPda, collateralVault, bondEscrow, yesMint]) {
+      assert.isNull(await provider.connection.getAccountInfo(account), `${account} should be closed`);
+    }
+    assert.isNotNull(await provider.connection.getAccountInfo(noMint), "NO tokens are still outstanding");
+    assert.equal((await getAccount(provider.connection, noToken, "confirmed", TOKEN_2022_PROGRAM_ID)).amount.toString(), "2000000");
+    assert.isAbove(await provider.connection.getBalance(authority.publicKey), creatorBefore);
+  });
+});
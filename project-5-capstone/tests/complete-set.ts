@@ -0,0 +1,388 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  transferChecked,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("complete sets", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+  const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+
+  const marketId = new anchor.BN(31);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let userCollateral: PublicKey;
+  let userYes: PublicKey;
+  let userNo: PublicKey;
+  let endTime: number;
+
+  const setAccounts = () => ({
+    user: user.publicKey,
+    pool: poolPda,
+    config: findConfigPda(program.programId),
+    collateralMint,
+    collateralVault,
+    userCollateral,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    collateralTokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  // Every outcome mint followed by the user's token account for it
+  const outcomeAccounts = () => [yesMint, userYes, noMint, userNo].map((pubkey) => ({
+    pubkey,
+    isWritable: true,
+    isSigner: false,
+  }));
+
+  const balance = async (account: PublicKey, programId = TOKEN_PROGRAM_ID) =>
+    (await getAccount(provider.connection, account, "confirmed", programId)).amount;
+  
+  const onlyEvent = async (signature: string, name: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [...parser.parseLogs(tx.meta.logMessages)].filter((event) => event.name === name);
+    assert.lengthOf(events, 1, `Expected one ${name} event`);
+    return events[0].data;
+  };
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a binary pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, user.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    userCollateral = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, authorityCollateral, authority, 5_000_000);
+    await mintTo(provider.connection, authority, collateralMint, userCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Complete sets",
+      description: "YES and NO minted together",
+    });
+    
+    userYes = await createAssociatedTokenAccount(provider.connection, user, yesMint, user.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+    userNo = await createAssociatedTokenAccount(provider.connection, user, noMint, user.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+  });
+
+  it("Complete sets need a market maker", async () => {
+    try {
+      await program.methods
+        .mintCompleteSet(new anchor.BN(1_000_000))
+        .accounts(setAccounts())
+        .remainingAccounts(outcomeAccounts())
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Sets should be rejected in a parimutuel pool");
+    } catch (error) {
+      assert.include(error.toString(), "CompleteSetsRequireAmm");
+    }
+    
+    await program.methods
+      .fundAmm(new anchor.BN(5_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        collateralVault,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Mints YES and NO together for 1 collateral each", async () => {
+    const signature = await program.methods
+      .mintCompleteSet(new anchor.BN(3_000_000))
+      .accounts(setAccounts())
+      .remainingAccounts(outcomeAccounts())
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal((await balance(userCollateral)).toString(), "7000000");
+    assert.equal((await balance(userYes, TOKEN_2022_PROGRAM_ID)).toString(), "3000000");
+    assert.equal((await balance(userNo, TOKEN_2022_PROGRAM_ID)).toString(), "3000000");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.totalCollateral.toString(), "8000000");
+    assert.equal(poolData.completeSets.toString(), "8000000");
+    
+    const minted = await onlyEvent(signature, "completeSetsMinted");
+    assert.ok(minted.user.equals(user.publicKey));
+    assert.equal(minted.amount.toNumber(), 3_000_000);
+  });
+
+  it("Rejects outcome accounts out of order", async () => {
+    try {
+      await program.methods
+        .mintCompleteSet(new anchor.BN(1_000_000))
+        .accounts(setAccounts())
+        .remainingAccounts(outcomeAccounts().reverse())
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Mismatched outcome accounts should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidOutcomeAccounts");
+    }
+  });
+
+  it("Half of a set cannot be burned for a full unit of collateral", async () => {
+    try {
+      await program.methods
+        .burnPredictionTokens(new anchor.BN(1_000_000), YES)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint: yesMint,
+          userOutcomeToken: userYes,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Single-sided burn of a complete set should fail");
+    } catch (error) {
+      assert.include(error.toString(), "AmmActive");
+    }
+  });
+
+  it("Redeems full sets after end_time", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    const signature = await program.methods
+      .redeemCompleteSet(new anchor.BN(2_000_000))
+      .accounts(setAccounts())
+      .remainingAccounts(outcomeAccounts())
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal((await balance(userCollateral)).toString(), "9000000");
+    assert.equal((await balance(userYes, TOKEN_2022_PROGRAM_ID)).toString(), "1000000");
+    assert.equal((await balance(userNo, TOKEN_2022_PROGRAM_ID)).toString(), "1000000");
+    assert.equal((await balance(collateralVault)).toString(), "6000000");
+    
+    const redeemed = await onlyEvent(signature, "completeSetsRedeemed");
+    assert.equal(redeemed.amount.toNumber(), 2_000_000);
+    assert.equal(redeemed.payout.toNumber(), 2_000_000, "Sets redeem at par before finalization");
+  });
+});
+
+describe("claim order", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const bettor = Keypair.generate();
+  const mallory = Keypair.generate();
+
+  const config = findConfigPda(program.programId);
+  const ammPool = findPoolPda(program.programId, authority.publicKey, new anchor.BN(191));
+  const betPool = findPoolPda(program.programId, authority.publicKey, new anchor.BN(192));
+  const outcomeToken = (pool: PublicKey, owner: Keypair, outcome = YES) =>
+    getAssociatedTokenAddressSync(findOutcomeMintPda(program.programId, pool, outcome), owner.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  
+  let collateralMint: PublicKey;
+  const collateral = new Map<Keypair, PublicKey>();
+  let endTime: number;
+
+  const balance = async (account: PublicKey) =>
+    (await getAccount(provider.connection, account, "confirmed", TOKEN_PROGRAM_ID)).amount;
+  
+  const tradeAccounts = (pool: PublicKey, owner: Keypair, outcome = YES) => ({
+    user: owner.publicKey,
+    pool,
+    config,
+    collateralMint,
+    collateralVault: findVaultPda(program.programId, pool),
+    userCollateral: collateral.get(owner),
+    outcomeMint: findOutcomeMintPda(program.programId, pool, outcome),
+    userOutcomeToken: outcomeToken(pool, owner, outcome),
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    collateralTokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  const fundAmm = (pool: PublicKey) =>
+    program.methods
+      .fundAmm(new anchor.BN(2_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool,
+        collateralMint,
+        collateralVault: findVaultPda(program.programId, pool),
+        authorityCollateral: collateral.get(authority),
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  // Hand half of the bettor's YES tokens to Mallory
+  const giveHalfToMallory = async (pool: PublicKey) => {
+    const yesMint = findOutcomeMintPda(program.programId, pool, YES);
+    await createAssociatedTokenAccount(provider.connection, mallory, yesMint, mallory.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+    await transferChecked(
+      provider.connection,
+      bettor,
+      outcomeToken(pool, bettor),
+      yesMint,
+      outcomeToken(pool, mallory),
+      bettor,
+      500_000,
+      6,
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+  };
+
+  const resolveYes = async (pool: PublicKey) => {
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    const proposed = await program.account.bettingPool.fetch(pool);
+    await waitUntil(provider.connection, proposed.disputePeriodEnd.toNumber());
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  // Claim every YES token the owner holds and return the collateral received
+  const claimYes = async (pool: PublicKey, owner: Keypair) => {
+    const before = await balance(collateral.get(owner));
+    const { associatedTokenProgram, systemProgram, outcomeMint, userOutcomeToken, ...accounts } = tradeAccounts(pool, owner);
+    await program.methods
+      .claimWinnings()
+      .accounts({ ...accounts, winningMint: outcomeMint, userWinningToken: userOutcomeToken })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+    return (await balance(collateral.get(owner))) - before;
+  };
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a market maker pool and a parimutuel pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    for (const owner of [authority, bettor, mallory]) {
+      if (owner !== authority) {
+        await airdrop(provider.connection, owner.publicKey, 1);
+      }
+      const ownerCollateral = await createAssociatedTokenAccount(provider.connection, owner, collateralMint, owner.publicKey);
+      await mintTo(provider.connection, authority, collateralMint, ownerCollateral, authority, 10_000_000);
+      collateral.set(owner, ownerCollateral);
+    }
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    for (const [marketId, name] of [[191, "Market maker"], [192, "Parimutuel"]] as const) {
+      await createPool(program, authority, new anchor.BN(marketId), collateralMint, {
+        endTime,
+        name,
+        description: "Claims in any order",
+      });
+    }
+    await fundAmm(ammPool);
+    
+    await program.methods
+      .buyOutcomeTokens(YES, new anchor.BN(1_000_000), new anchor.BN(1_000_000))
+      .accounts(tradeAccounts(ammPool, bettor))
+      .signers([bettor])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .mintPredictionTokens(new anchor.BN(1_000_000), YES)
+      .accounts({ ...tradeAccounts(betPool, bettor), rent: anchor.web3.SYSVAR_RENT_PUBKEY })
+      .signers([bettor])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .mintPredictionTokens(new anchor.BN(3_000_000), NO)
+      .accounts({ ...tradeAccounts(betPool, authority, NO), rent: anchor.web3.SYSVAR_RENT_PUBKEY })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("A pool holding bets cannot take a market maker", async () => {
+    try {
+      await fundAmm(betPool);
+      assert.fail("Sets should not be mixed with bets");
+    } catch (error) {
+      assert.include(error.toString(), "PoolHasBets");
+    }
+  });
+
+  it("A transferred token claiming first takes no more than its share", async () => {
+    await giveHalfToMallory(ammPool);
+    await giveHalfToMallory(betPool);
+    
+    await waitUntil(provider.connection, endTime);
+    await resolveYes(ammPool);
+    await resolveYes(betPool);
+    
+    // Market maker tokens are worth one unit each, whoever claims first
+    assert.equal((await claimYes(ammPool, mallory)).toString(), "500000");
+    assert.equal((await claimYes(ammPool, bettor)).toString(), "500000");
+    
+    // The 4M pot is split evenly over the 1M YES tokens
+    assert.equal((await claimYes(betPool, mallory)).toString(), "2000000");
+    assert.equal((await claimYes(betPool, bettor)).toString(), "2000000");
+    assert.equal((await balance(findVaultPda(program.programId, betPool))).toString(), "0");
+  });
+});
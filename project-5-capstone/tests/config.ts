@@ -0,0 +1,158 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  createMint,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  findPoolPda,
+  initializePool,
+  PoolParams,
+  ensureConfig,
+  findConfigPda,
+  findProgramDataPda,
+} from "./helpers";
+
+describe("global config", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const admin = provider.wallet.publicKey;
+  const config = findConfigPda(program.programId);
+
+  let allowedMint: PublicKey;
+  let otherMint: PublicKey;
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      assert.fail(`Expected ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  };
+
+  const initializeConfigPool = (
+    marketId: number,
+    collateralMint: PublicKey,
+    disputePeriodSeconds: number,
+    params: Partial<PoolParams> = {}
+  ) =>
+    initializePool(program, authority, new anchor.BN(marketId), collateralMint, {
+      endTime: Math.floor(Date.now() / 1000) + 60,
+      disputePeriodSeconds,
+      name: "Config pool",
+      description: "Checked against the global config",
+      ...params,
+    });
+  
+  before(async () => {
+    await ensureConfig(program);
+    await airdrop(provider.connection, authority.publicKey, 2);
+    allowedMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    otherMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+  });
+
+  it("Only the upgrade authority can initialize the config", async () => {
+    await expectError(
+      program.methods
+        .initializeConfig(authority.publicKey, new anchor.BN(0), new anchor.BN(1), new anchor.BN(60))
+        .accounts({
+          admin: authority.publicKey,
+          program: program.programId,
+          programData: findProgramDataPda(program.programId),
+          config,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" }),
+      "Unauthorized"
+    );
+  });
+
+  it("Only the admin can change the config", async () => {
+    await expectError(
+      program.methods
+        .setPoolCreationPaused(true)
+        .accounts({ admin: authority.publicKey, config })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" }),
+      "Unauthorized"
+    );
+  });
+
+  it("Dispute periods must fall within the configured bounds", async () => {
+    await program.methods
+      .updateDisputePeriodBounds(new anchor.BN(10), new anchor.BN(3_600))
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+    
+    await expectError(initializeConfigPool(101, allowedMint, 5), "InvalidDisputePeriod");
+    await expectError(initializeConfigPool(101, allowedMint, 7_200), "InvalidDisputePeriod");
+    await expectError(initializeConfigPool(101, allowedMint, -60), "InvalidDisputePeriod");
+    
+    // Restore the bounds the rest of the suite creates pools under
+    await program.methods
+      .updateDisputePeriodBounds(new anchor.BN(1), new anchor.BN(30 * 24 * 60 * 60))
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Only allowed collateral mints can back a pool once a list is set", async () => {
+    await program.methods
+      .setCollateralMintAllowed(allowedMint, true)
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+    
+    await expectError(initializeConfigPool(102, otherMint, 60), "CollateralMintNotAllowed");
+    await initializeConfigPool(102, allowedMint, 60);
+    
+    await program.methods
+      .setCollateralMintAllowed(allowedMint, false)
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+    const configData = await program.account.globalConfig.fetch(config);
+    assert.lengthOf(configData.allowedCollateralMints, 0);
+  });
+
+  it("No pool can be created while creation is paused", async () => {
+    await program.methods
+      .setPoolCreationPaused(true)
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+    
+    await expectError(initializeConfigPool(103, otherMint, 60), "PoolCreationPaused");
+    
+    await program.methods
+      .setPoolCreationPaused(false)
+      .accounts({ admin, config })
+      .rpc({ commitment: "confirmed" });
+    await initializeConfigPool(103, otherMint, 60);
+  });
+
+  it("Pools must end in the future and fit their name and description", async () => {
+    const past = Math.floor(Date.now() / 1000) - 60;
+    await expectError(initializeConfigPool(104, otherMint, 60, { endTime: past }), "EndTimeInPast");
+    await expectError(initializeConfigPool(104, otherMint, 60, { name: "n".repeat(33) }), "PoolNameTooLong");
+    await expectError(
+      initializeConfigPool(104, otherMint, 60, { description: "d".repeat(257) }),
+      "PoolDescriptionTooLong"
+    );
+    
+    await initializeConfigPool(104, otherMint, 60, { name: "n".repeat(32), description: "d".repeat(256) });
+    const poolData = await program.account.bettingPool.fetch(
+      findPoolPda(program.programId, authority.publicKey, new anchor.BN(104))
+    );
+    assert.lengthOf(poolData.name, 32);
+    assert.lengthOf(poolData.description, 256);
+  });
+});
@@ -0,0 +1,214 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("dispute rounds", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const yesUser = Keypair.generate();
+  const noUser = Keypair.generate();
+
+  const marketId = new anchor.BN(81);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const yesToken = getAssociatedTokenAddressSync(yesMint, yesUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const noToken = getAssociatedTokenAddressSync(noMint, noUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let yesCollateral: PublicKey;
+  let noCollateral: PublicKey;
+  let endTime: number;
+
+  const balance = async (account: PublicKey) => (await getAccount(provider.connection, account)).amount;
+
+  const dispute = (user: Keypair, outcome: number, outcomeMint: PublicKey, userOutcomeToken: PublicKey, userCollateral: PublicKey) =>
+    program.methods
+      .disputeSolution(outcome)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        outcomeMint,
+        userOutcomeToken,
+        collateralMint,
+        bondEscrow,
+        userCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+  
+  const resolve = (newWinner: number, disputerCollateral: PublicKey) =>
+    program.methods
+      .resolveDispute(newWinner)
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        disputerCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a pool with a 100k dispute bond", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, yesUser.publicKey, 1);
+    await airdrop(provider.connection, noUser.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    yesCollateral = await createAssociatedTokenAccount(provider.connection, yesUser, collateralMint, yesUser.publicKey);
+    noCollateral = await createAssociatedTokenAccount(provider.connection, noUser, collateralMint, noUser.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, yesCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, noCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      disputePeriodSeconds: 30,
+      name: "Escalating pool",
+      description: "Every dispute round costs more",
+    });
+    
+    await program.methods
+      .configureBonds(new anchor.BN(0), new anchor.BN(100_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    for (const [user, userCollateral, outcome, outcomeMint, userOutcomeToken] of [
+      [yesUser, yesCollateral, YES, yesMint, yesToken],
+      [noUser, noCollateral, NO, noMint, noToken],
+    ] as const) {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(4_000_000), outcome)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint,
+          userOutcomeToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("Round one: a 100k dispute is rejected", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await dispute(noUser, NO, noMint, noToken, noCollateral);
+    assert.equal((await balance(noCollateral)).toString(), "5900000");
+    
+    await resolve(YES, noCollateral);
+    assert.equal((await balance(authorityCollateral)).toString(), "100000");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.disputeRound, 1);
+    assert.isFalse(poolData.isFinalized);
+  });
+
+  it("Round two: a 200k dispute overturns the answer", async () => {
+    await dispute(noUser, NO, noMint, noToken, noCollateral);
+    assert.equal((await balance(noCollateral)).toString(), "5700000");
+    
+    await resolve(NO, noCollateral);
+    assert.equal((await balance(noCollateral)).toString(), "5900000");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.disputeRound, 2);
+    assert.equal(poolData.solutionWinner, NO);
+  });
+
+  it("Round three needs twice the tokens of round two", async () => {
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.disputeThreshold.toNumber() * 4, 4_000_000);
+    
+    // The YES holder has exactly the 4M tokens the final round asks for
+    await dispute(yesUser, YES, yesMint, yesToken, yesCollateral);
+    assert.equal((await balance(yesCollateral)).toString(), "5600000");
+  });
+
+  it("The final round finalizes the pool for good", async () => {
+    await resolve(NO, yesCollateral);
+    assert.equal((await balance(authorityCollateral)).toString(), "500000");
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.disputeRound, 3);
+    assert.isTrue(poolData.isFinalized);
+    assert.equal(poolData.outcomes[NO].payoutPool.toString(), "8000000");
+    
+    try {
+      await dispute(yesUser, YES, yesMint, yesToken, yesCollateral);
+      assert.fail("A finalized pool cannot be disputed again");
+    } catch (error) {
+      assert.include(error.toString(), "AlreadyFinalized");
+    }
+  });
+});
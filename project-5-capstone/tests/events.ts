@@ -0,0 +1,254 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  initializePool,
+  initializeOutcomes,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("events", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+  const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+  const disputer = Keypair.generate();
+
+  const marketId = new anchor.BN(131);
+  const config = findConfigPda(program.programId);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const userYes = getAssociatedTokenAddressSync(yesMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const disputerNo = getAssociatedTokenAddressSync(noMint, disputer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let userCollateral: PublicKey;
+  let disputerCollateral: PublicKey;
+  let endTime: number;
+
+  // Events are read back from the logs of the confirmed transaction
+  const eventsOf = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return [...parser.parseLogs(tx.meta.logMessages)];
+  };
+
+  const onlyEvent = async (signature: string, name: string) => {
+    const events = (await eventsOf(signature)).filter((event) => event.name === name);
+    assert.lengthOf(events, 1, `Expected one ${name} event`);
+    assert.ok(events[0].data.pool.equals(poolPda));
+    return events[0].data;
+  };
+
+  const mint = (owner: Keypair, ownerCollateral: PublicKey, outcome: number, outcomeMint: PublicKey, ownerOutcome: PublicKey) =>
+    program.methods
+      .mintPredictionTokens(new anchor.BN(2_000_000), outcome)
+      .accounts({
+        user: owner.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral: ownerCollateral,
+        outcomeMint,
+        userOutcomeToken: ownerOutcome,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Pool creation is announced", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, user.publicKey, 1);
+    await airdrop(provider.connection, disputer.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    userCollateral = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+    disputerCollateral = await createAssociatedTokenAccount(provider.connection, disputer, collateralMint, disputer.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, userCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, disputerCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 15;
+    const signature = await initializePool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Indexed pool",
+      description: "Every step is emitted",
+    });
+    
+    const created = await onlyEvent(signature, "poolCreated");
+    assert.ok(created.authority.equals(authority.publicKey));
+    assert.equal(created.marketId.toNumber(), 131);
+    assert.equal(created.outcomeCount, 2);
+    assert.equal(created.endTime.toNumber(), endTime);
+    
+    await initializeOutcomes(program, authority, poolPda, collateralMint, ["YES", "NO"]);
+  });
+
+  it("Mints and burns carry the user, outcome and amount", async () => {
+    const minted = await onlyEvent(await mint(user, userCollateral, YES, yesMint, userYes), "tokensMinted");
+    assert.ok(minted.user.equals(user.publicKey));
+    assert.equal(minted.outcome, YES);
+    assert.equal(minted.amount.toNumber(), 2_000_000);
+    assert.isAbove(minted.timestamp.toNumber(), 0);
+    
+    await mint(disputer, disputerCollateral, NO, noMint, disputerNo);
+    
+    const signature = await program.methods
+      .burnPredictionTokens(new anchor.BN(500_000), YES)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral,
+        outcomeMint: yesMint,
+        userOutcomeToken: userYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    const burned = await onlyEvent(signature, "tokensBurned");
+    assert.ok(burned.user.equals(user.publicKey));
+    assert.equal(burned.outcome, YES);
+    assert.equal(burned.amount.toNumber(), 500_000);
+  });
+
+  it("Proposal, dispute and resolution are emitted in turn", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    let signature = await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    const proposed = await onlyEvent(signature, "solutionProposed");
+    assert.ok(proposed.proposer.equals(authority.publicKey));
+    assert.equal(proposed.winner, YES);
+    assert.isNull(proposed.value);
+    assert.equal(proposed.disputePeriodEnd.toNumber(), proposed.timestamp.toNumber() + 2);
+    
+    signature = await program.methods
+      .disputeSolution(NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config,
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        collateralMint,
+        bondEscrow,
+        userCollateral: disputerCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+    const disputed = await onlyEvent(signature, "solutionDisputed");
+    assert.ok(disputed.disputer.equals(disputer.publicKey));
+    assert.equal(disputed.outcome, NO);
+    assert.equal(disputed.tokens.toNumber(), 2_000_000);
+    assert.equal(disputed.bond.toNumber(), 0);
+    assert.equal(disputed.round, 0);
+    
+    signature = await program.methods
+      .resolveDispute(YES)
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        disputerCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    const resolved = await onlyEvent(signature, "disputeResolved");
+    assert.equal(resolved.winner, YES);
+    assert.isTrue(resolved.upheld);
+    assert.equal(resolved.round, 0);
+    assert.lengthOf((await eventsOf(signature)).filter((event) => event.name === "poolFinalized"), 0);
+  });
+
+  it("Finalization and claims are emitted", async () => {
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, poolData.disputePeriodEnd.toNumber());
+    
+    let signature = await program.methods
+      .finalizePool()
+      .accounts({ user: user.publicKey, pool: poolPda })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    const finalized = await onlyEvent(signature, "poolFinalized");
+    assert.equal(finalized.winner, YES);
+    
+    signature = await program.methods
+      .claimWinnings()
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        winningMint: yesMint,
+        userWinningToken: userYes,
+        userCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    const claimed = await onlyEvent(signature, "winningsClaimed");
+    assert.ok(claimed.user.equals(user.publicKey));
+    assert.equal(claimed.outcome, YES);
+    assert.equal(claimed.tokens.toNumber(), 1_500_000);
+    assert.equal(claimed.payout.toNumber() + claimed.fees.toNumber(), 3_500_000);
+  });
+});
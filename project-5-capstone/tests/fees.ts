@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("fees", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const treasury = Keypair.generate();
+  const yesUser = Keypair.generate();
+  const noUser = Keypair.generate();
+
+  const marketId = new anchor.BN(91);
+  const config = findConfigPda(program.programId);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const yesToken = getAssociatedTokenAddressSync(yesMint, yesUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const noToken = getAssociatedTokenAddressSync(noMint, noUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let treasuryCollateral: PublicKey;
+  let yesCollateral: PublicKey;
+  let noCollateral: PublicKey;
+  let endTime: number;
+
+  const balance = async (account: PublicKey) => (await getAccount(provider.connection, account)).amount;
+
+  const setProtocolFee = (treasury: PublicKey, protocolFeeBps: number) =>
+    program.methods
+      .updateProtocolFee(treasury, new anchor.BN(protocolFeeBps))
+      .accounts({ admin: provider.wallet.publicKey, config })
+      .rpc({ commitment: "confirmed" });
+  
+  const configureCreatorFee = (creatorFeeBps: number) =>
+    program.methods
+      .configureCreatorFee(new anchor.BN(creatorFeeBps))
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  const withdrawFees = (recipient: Keypair, recipientCollateral: PublicKey) =>
+    program.methods
+      .withdrawFees()
+      .accounts({
+        recipient: recipient.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        recipientCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([recipient])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  // Later pools elsewhere in the suite expect no protocol fee
+  after(async () => {
+    await setProtocolFee(provider.wallet.publicKey, 0);
+  });
+
+  it("Set up a pool created under a 1% protocol fee", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, treasury.publicKey, 1);
+    await airdrop(provider.connection, yesUser.publicKey, 1);
+    await airdrop(provider.connection, noUser.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    treasuryCollateral = await createAssociatedTokenAccount(provider.connection, treasury, collateralMint, treasury.publicKey);
+    yesCollateral = await createAssociatedTokenAccount(provider.connection, yesUser, collateralMint, yesUser.publicKey);
+    noCollateral = await createAssociatedTokenAccount(provider.connection, noUser, collateralMint, noUser.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, yesCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, noCollateral, authority, 10_000_000);
+    
+    await setProtocolFee(treasury.publicKey, 100);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Fee pool",
+      description: "Winnings pay the protocol and the creator",
+    });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.protocolFeeBps.toNumber(), 100);
+  });
+
+  it("The creator fee is capped", async () => {
+    try {
+      await configureCreatorFee(950);
+      assert.fail("Fees above the cap should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "FeeTooHigh");
+    }
+    
+    await configureCreatorFee(200);
+  });
+
+  it("The creator fee is locked once bets come in", async () => {
+    for (const [user, userCollateral, outcome, outcomeMint, userOutcomeToken, amount] of [
+      [yesUser, yesCollateral, YES, yesMint, yesToken, 6_000_000],
+      [noUser, noCollateral, NO, noMint, noToken, 4_000_000],
+    ] as const) {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(amount), outcome)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint,
+          userOutcomeToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+    
+    try {
+      await configureCreatorFee(0);
+      assert.fail("The fee should not change under open bets");
+    } catch (error) {
+      assert.include(error.toString(), "FeesLocked");
+    }
+  });
+
+  it("The winner's claim is charged both fees", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const proposed = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, proposed.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .claimWinnings()
+      .accounts({
+        user: yesUser.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        userCollateral: yesCollateral,
+        winningMint: yesMint,
+        userWinningToken: yesToken,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([yesUser])
+      .rpc({ commitment: "confirmed" });
+    
+    // 10M pot, less 1% to the protocol and 2% to the creator
+    assert.equal((await balance(yesCollateral)).toString(), "13700000");
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.protocolFees.toString(), "100000");
+    assert.equal(poolData.creatorFees.toString(), "200000");
+  });
+
+  it("Each recipient withdraws only its own fees", async () => {
+    try {
+      await withdrawFees(noUser, noCollateral);
+      assert.fail("Only fee recipients can withdraw");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+    
+    await withdrawFees(authority, authorityCollateral);
+    assert.equal((await balance(authorityCollateral)).toString(), "200000");
+    
+    await withdrawFees(treasury, treasuryCollateral);
+    assert.equal((await balance(treasuryCollateral)).toString(), "100000");
+    
+    try {
+      await withdrawFees(authority, authorityCollateral);
+      assert.fail("Fees cannot be withdrawn twice");
+    } catch (error) {
+      assert.include(error.toString(), "NoFeesToWithdraw");
+    }
+    
+    assert.equal((await balance(collateralVault)).toString(), "0");
+  });
+});
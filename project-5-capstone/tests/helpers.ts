@@ -0,0 +1,270 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Connection,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  MINT_SIZE,
+  MintLayout,
+  ACCOUNT_SIZE,
+  AccountLayout,
+  AccountState,
+} from '@solana/spl-token';
+import { ProgramTestContext } from "solana-bankrun";
+
+export const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+export async function airdrop(connection: Connection, to: PublicKey, sol = 2) {
+  const signature = await connection.requestAirdrop(to, sol * LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(signature);
+}
+
+// Wait until the cluster clock has passed `unixTimestamp`
+export async function waitUntil(connection: Connection, unixTimestamp: number) {
+  while (true) {
+    const slot = await connection.getSlot("confirmed");
+    const blockTime = await connection.getBlockTime(slot);
+    if (blockTime !== null && blockTime > unixTimestamp) {
+      return;
+    }
+    await sleep(500);
+  }
+}
+
+export function findConfigPda(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync([Buffer.from("config")], programId)[0];
+}
+
+export function findProgramDataPda(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync([programId.toBuffer()], BPF_LOADER_UPGRADEABLE_PROGRAM_ID)[0];
+}
+
+// Create the global config the first time a test needs it, with the provider wallet
+// as admin and treasury, no protocol fee and dispute periods of up to 30 days.
+// The provider wallet must be the program's upgrade authority.
+export async function ensureConfig(program: anchor.Program<any>) {
+  const config = findConfigPda(program.programId);
+  if (await program.account.globalConfig.fetchNullable(config)) {
+    return config;
+  }
+
+  const admin = program.provider.publicKey;
+  await program.methods
+    .initializeConfig(admin, new anchor.BN(0), new anchor.BN(1), new anchor.BN(30 * 24 * 60 * 60))
+    .accounts({
+      admin,
+      program: program.programId,
+      programData: findProgramDataPda(program.programId),
+      config,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc({ commitment: "confirmed" });
+  return config;
+}
+
+// Bankrun loads programs without an upgrade authority, so write the config that
+// `ensureConfig` would create straight into the bank
+export async function setConfig(context: ProgramTestContext, program: anchor.Program<any>) {
+  const [config, bump] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+  const admin = context.payer.publicKey;
+  const data = await program.coder.accounts.encode("GlobalConfig", {
+    admin,
+    treasury: admin,
+    protocolFeeBps: new anchor.BN(0),
+    minDisputePeriodSeconds: new anchor.BN(1),
+    maxDisputePeriodSeconds: new anchor.BN(30 * 24 * 60 * 60),
+    allowedCollateralMints: [],
+    poolCreationPaused: false,
+    guardian: admin,
+    unpauseDelaySeconds: new anchor.BN(24 * 60 * 60),
+    pause: { paused: false, unpauseAt: null },
+    bump,
+  });
+  context.setAccount(config, {
+    lamports: LAMPORTS_PER_SOL,
+    data,
+    owner: program.programId,
+    executable: false,
+  });
+  return config;
+}
+
+export function findPoolPda(programId: PublicKey, authority: PublicKey, marketId: anchor.BN) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pool"), authority.toBuffer(), marketId.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+export function findVaultPda(programId: PublicKey, pool: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), pool.toBuffer()],
+    programId
+  )[0];
+}
+
+export function findBondEscrowPda(programId: PublicKey, pool: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("bond_escrow"), pool.toBuffer()],
+    programId
+  )[0];
+}
+
+export function findOutcomeMintPda(programId: PublicKey, pool: PublicKey, outcome: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("outcome_mint"), pool.toBuffer(), Buffer.from([outcome])],
+    programId
+  )[0];
+}
+
+export type PoolParams = {
+  endTime: number;
+  marketKind?: object;
+  resolutionSource?: object;
+  outcomeCount?: number;
+  disputePeriodSeconds?: number;
+  disputeThreshold?: number;
+  name?: string;
+  description?: string;
+};
+
+// Send `initialize_pool` for `authority`'s market `marketId`. Pools default to a
+// manually resolved YES/NO market with a 2 second dispute period.
+export function initializePool(
+  program: anchor.Program<any>,
+  authority: Keypair,
+  marketId: anchor.BN,
+  collateralMint: PublicKey,
+  {
+    endTime,
+    marketKind = { categorical: {} },
+    resolutionSource = { manual: {} },
+    outcomeCount = 2,
+    disputePeriodSeconds = 2,
+    disputeThreshold = 1_000_000,
+    name = "Test pool",
+    description = "Created by the test suite",
+  }: PoolParams
+) {
+  const pool = findPoolPda(program.programId, authority.publicKey, marketId);
+  return program.methods
+    .initializePool(
+      marketId,
+      marketKind,
+      resolutionSource,
+      outcomeCount,
+      new anchor.BN(disputePeriodSeconds),
+      new anchor.BN(disputeThreshold),
+      name,
+      description,
+      new anchor.BN(endTime)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      config: findConfigPda(program.programId),
+      pool,
+      collateralMint,
+      collateralVault: findVaultPda(program.programId, pool),
+      bondEscrow: findBondEscrowPda(program.programId, pool),
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    })
+    .signers([authority])
+    .rpc({ commitment: "confirmed" });
+}
+
+export type OutcomeParams = {
+  uri?: (label: string) => string;
+  // Extra accounts for each outcome mint, such as its transfer hook's
+  remainingAccounts?: (outcomeMint: PublicKey) => AccountMeta[];
+};
+
+// Create one outcome mint per label, in order
+export async function initializeOutcomes(
+  program: anchor.Program<any>,
+  authority: Keypair,
+  pool: PublicKey,
+  collateralMint: PublicKey,
+  labels: string[],
+  { uri = () => "", remainingAccounts = () => [] }: OutcomeParams = {}
+) {
+  for (let index = 0; index < labels.length; index++) {
+    const outcomeMint = findOutcomeMintPda(program.programId, pool, index);
+    await program.methods
+      .initializeOutcome(index, labels[index], uri(labels[index]))
+      .accounts({
+        authority: authority.publicKey,
+        pool,
+        collateralMint,
+        outcomeMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts(outcomeMint))
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  }
+}
+
+// Create a pool with every outcome ready for trading and return its address
+export async function createPool(
+  program: anchor.Program<any>,
+  authority: Keypair,
+  marketId: anchor.BN,
+  collateralMint: PublicKey,
+  params: PoolParams & OutcomeParams & { labels?: string[] }
+) {
+  const labels = params.labels ?? ["YES", "NO"];
+  await initializePool(program, authority, marketId, collateralMint, { ...params, outcomeCount: labels.length });
+  const pool = findPoolPda(program.programId, authority.publicKey, marketId);
+  await initializeOutcomes(program, authority, pool, collateralMint, labels, params);
+  return pool;
+}
+
+// An initialized SPL mint with no authority, for loading straight into bankrun
+export function mintAccount(decimals: number) {
+  const data = Buffer.alloc(MINT_SIZE);
+  MintLayout.encode(
+    {
+      mintAuthorityOption: 0,
+      mintAuthority: PublicKey.default,
+      supply: BigInt(0),
+      decimals,
+      isInitialized: true,
+      freezeAuthorityOption: 0,
+      freezeAuthority: PublicKey.default,
+    },
+    data
+  );
+  return { lamports: LAMPORTS_PER_SOL, data, owner: TOKEN_PROGRAM_ID, executable: false };
+}
+
+// An initialized token account holding `amount` of `mint`, for loading straight into bankrun
+export function tokenAccount(mint: PublicKey, owner: PublicKey, amount = BigInt(0), programId = TOKEN_PROGRAM_ID) {
+  const data = Buffer.alloc(ACCOUNT_SIZE);
+  AccountLayout.encode(
+    {
+      mint,
+      owner,
+      amount,
+      delegateOption: 0,
+      delegate: PublicKey.default,
+      state: AccountState.Initialized,
+      isNativeOption: 0,
+      isNative: BigInt(0),
+      delegatedAmount: BigInt(0),
+      closeAuthorityOption: 0,
+      closeAuthority: PublicKey.default,
+    },
+    data
+  );
+  return { lamports: LAMPORTS_PER_SOL, data, owner: programId, executable: false };
+}
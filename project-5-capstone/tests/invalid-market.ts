@@ -0,0 +1,194 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+const INVALID = 255;
+
+describe("invalid market", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const yesUser = Keypair.generate();
+  const noUser = Keypair.generate();
+
+  const marketId = new anchor.BN(71);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const yesToken = getAssociatedTokenAddressSync(yesMint, yesUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const noToken = getAssociatedTokenAddressSync(noMint, noUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let yesCollateral: PublicKey;
+  let noCollateral: PublicKey;
+  let endTime: number;
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a binary pool with bets on both sides", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, yesUser.publicKey, 1);
+    await airdrop(provider.connection, noUser.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    yesCollateral = await createAssociatedTokenAccount(provider.connection, yesUser, collateralMint, yesUser.publicKey);
+    noCollateral = await createAssociatedTokenAccount(provider.connection, noUser, collateralMint, noUser.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, yesCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, noCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Cancelled match",
+      description: "Who wins a match that may never be played?",
+    });
+    
+    for (const [user, userCollateral, outcome, outcomeMint, userOutcomeToken, amount] of [
+      [yesUser, yesCollateral, YES, yesMint, yesToken, 3_000_000],
+      [noUser, noCollateral, NO, noMint, noToken, 1_000_000],
+    ] as const) {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(amount), outcome)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint,
+          userOutcomeToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("An invalid answer can be disputed and upheld", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(INVALID)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    // The YES holder expected to win and disputes the cancellation
+    await program.methods
+      .disputeSolution(YES)
+      .accounts({
+        user: yesUser.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        outcomeMint: yesMint,
+        userOutcomeToken: yesToken,
+        collateralMint,
+        bondEscrow,
+        userCollateral: yesCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([yesUser])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .resolveDispute(INVALID)
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        disputerCollateral: yesCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const resolved = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(resolved.solutionWinner, INVALID);
+    await waitUntil(provider.connection, resolved.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.outcomes[YES].payoutPool.toString(), "3000000");
+    assert.equal(poolData.outcomes[NO].payoutPool.toString(), "1000000");
+  });
+
+  it("Both sides are refunded their stake", async () => {
+    for (const [user, userCollateral, winningMint, userWinningToken] of [
+      [yesUser, yesCollateral, yesMint, yesToken],
+      [noUser, noCollateral, noMint, noToken],
+    ] as const) {
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          winningMint,
+          userWinningToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+      
+      const after = await getAccount(provider.connection, userCollateral);
+      assert.equal(after.amount.toString(), "10000000");
+    }
+    
+    const vault = await getAccount(provider.connection, collateralVault);
+    assert.equal(vault.amount.toString(), "0", "Vault should be empty after every refund");
+  });
+});
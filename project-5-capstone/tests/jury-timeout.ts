@@ -0,0 +1,167 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { startAnchor, Clock, ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  findPoolPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  findConfigPda,
+  mintAccount,
+  tokenAccount,
+  createPool,
+  setConfig,
+} from "./helpers";
+
+const IDL = require("../target/idl/project_5_capstone.json");
+
+const YES = 0;
+const NO = 1;
+
+// Time after a dispute is opened from which the operator can step in for the jury
+const JURY_DRAW_TIMEOUT_SECONDS = 3 * 24 * 60 * 60;
+
+describe("jury draw timeout", () => {
+  const collateralMint = Keypair.generate().publicKey;
+  const governanceMint = Keypair.generate().publicKey;
+  const disputer = Keypair.generate();
+  const marketId = new anchor.BN(71);
+
+  let context: ProgramTestContext;
+  let program: Program<any>;
+  let authority: PublicKey;
+  let poolPda: PublicKey;
+  let authorityCollateral: PublicKey;
+  let disputerCollateral: PublicKey;
+  let endTime: number;
+  let disputedAt: number;
+
+  // Move the bank clock, and the slot with it so repeated transactions are not deduplicated
+  const setTime = async (unixTimestamp: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(clock.slot + 1n, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp))
+    );
+  };
+
+  const resolveDispute = () =>
+    program.methods
+      .resolveDispute(NO)
+      .accounts({
+        authority,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow: findBondEscrowPda(program.programId, poolPda),
+        authorityCollateral,
+        disputerCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  
+  before(async () => {
+    context = await startAnchor(".", [], [
+      { address: collateralMint, info: mintAccount(6) },
+      { address: governanceMint, info: mintAccount(0) },
+    ]);
+    const provider = new BankrunProvider(context);
+    program = new Program(IDL, provider);
+    await setConfig(context, program);
+    authority = context.payer.publicKey;
+    poolPda = findPoolPda(program.programId, authority, marketId);
+    
+    authorityCollateral = Keypair.generate().publicKey;
+    disputerCollateral = Keypair.generate().publicKey;
+    context.setAccount(authorityCollateral, tokenAccount(collateralMint, authority));
+    context.setAccount(disputerCollateral, tokenAccount(collateralMint, disputer.publicKey));
+    
+    const clock = await context.banksClient.getClock();
+    endTime = Number(clock.unixTimestamp) + 100;
+  });
+
+  it("Creates a jury pool while no juror is staked", async () => {
+    const [registry] = PublicKey.findProgramAddressSync([Buffer.from("jury_registry")], program.programId);
+    const [stakeVault] = PublicKey.findProgramAddressSync([Buffer.from("jury_stake_vault")], program.programId);
+    await program.methods
+      .initializeJuryRegistry(new anchor.BN(100), 3, new anchor.BN(4), new anchor.BN(4), new anchor.BN(1_000))
+      .accounts({
+        admin: authority,
+        config: findConfigPda(program.programId),
+        registry,
+        governanceMint,
+        stakeVault,
+        governanceTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    
+    await createPool(program, context.payer, marketId, collateralMint, {
+      endTime,
+      disputeThreshold: 0,
+      name: "Jury pool without jurors",
+      description: "Nobody can be drawn for its disputes",
+    });
+    await program.methods
+      .enableJuryResolution()
+      .accounts({ authority, pool: poolPda })
+      .rpc();
+  });
+
+  it("The operator cannot step in while a jury could still be drawn", async () => {
+    await setTime(endTime + 1);
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority, pool: poolPda })
+      .rpc();
+    
+    const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+    const disputerNo = getAssociatedTokenAddressSync(noMint, disputer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    context.setAccount(disputerNo, tokenAccount(noMint, disputer.publicKey, BigInt(0), TOKEN_2022_PROGRAM_ID));
+    await program.methods
+      .disputeSolution(NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        collateralMint,
+        bondEscrow: findBondEscrowPda(program.programId, poolPda),
+        userCollateral: disputerCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([disputer])
+      .rpc();
+    disputedAt = (await program.account.bettingPool.fetch(poolPda)).disputedAt.toNumber();
+    
+    await setTime(disputedAt + JURY_DRAW_TIMEOUT_SECONDS - 1);
+    try {
+      await resolveDispute();
+      assert.fail("The jury should still have its chance");
+    } catch (error) {
+      assert.include(error.toString(), "JuryResolutionEnabled");
+    }
+  });
+
+  it("The operator resolves the dispute once no jury was drawn in time", async () => {
+    await setTime(disputedAt + JURY_DRAW_TIMEOUT_SECONDS);
+    await resolveDispute();
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.solutionWinner, NO);
+    assert.isFalse(poolData.isDisputed);
+    assert.equal(poolData.disputeRound, 1);
+  });
+});
@@ -0,0 +1,447 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+  SYSVAR_SLOT_HASHES_PUBKEY,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { createHash, randomBytes } from "crypto";
+import { assert } from "chai";
+import {
+  airdrop,
+  sleep,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findBondEscrowPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+// Slots after a dispute is opened before its jury can be drawn
+const JURY_DRAW_DELAY_SLOTS = 8;
+
+describe("jury disputes", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const disputer = Keypair.generate();
+  const jurors = [Keypair.generate(), Keypair.generate(), Keypair.generate(), Keypair.generate()];
+
+  const marketId = new anchor.BN(61);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const bondEscrow = findBondEscrowPda(program.programId, poolPda);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const disputerNo = getAssociatedTokenAddressSync(noMint, disputer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const [registry] = PublicKey.findProgramAddressSync([Buffer.from("jury_registry")], program.programId);
+  const [stakeVault] = PublicKey.findProgramAddressSync([Buffer.from("jury_stake_vault")], program.programId);
+  const [panel] = PublicKey.findProgramAddressSync([Buffer.from("jury_panel"), poolPda.toBuffer()], program.programId);
+
+  let collateralMint: PublicKey;
+  let governanceMint: PublicKey;
+  let authorityCollateral: PublicKey;
+  let disputerCollateral: PublicKey;
+  let jurorTokens: PublicKey[] = [];
+  let endTime: number;
+
+  const balance = async (account: PublicKey) => (await getAccount(provider.connection, account)).amount;
+  const jurorFor = (key: PublicKey) => jurors.findIndex((juror) => juror.publicKey.equals(key));
+  const commitment = (juror: PublicKey, outcome: number, salt: Buffer) =>
+    [...createHash("sha256").update(Buffer.concat([juror.toBuffer(), Buffer.from([outcome]), salt])).digest()];
+  const stakeOf = async (juror: Keypair) => {
+    const registryData = await program.account.juryRegistry.fetch(registry);
+    return registryData.jurors.find((entry) => entry.juror.equals(juror.publicKey)).stake.toNumber();
+  };
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up the registry, jurors and a jury-resolved pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, disputer.publicKey, 1);
+    for (const juror of jurors) {
+      await airdrop(provider.connection, juror.publicKey, 1);
+    }
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    governanceMint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    disputerCollateral = await createAssociatedTokenAccount(provider.connection, disputer, collateralMint, disputer.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, authorityCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, disputerCollateral, authority, 10_000_000);
+    
+    const initializeRegistry = (admin: PublicKey) =>
+      program.methods
+        .initializeJuryRegistry(new anchor.BN(100), 3, new anchor.BN(4), new anchor.BN(4), new anchor.BN(1_000))
+        .accounts({
+          admin,
+          config: findConfigPda(program.programId),
+          registry,
+          governanceMint,
+          stakeVault,
+          governanceTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        });
+    
+    try {
+      await initializeRegistry(authority.publicKey).signers([authority]).rpc({ commitment: "confirmed" });
+      assert.fail("Only the config admin can set up the registry");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+    
+    // Panels of three, 10% of stake slashed from jurors outside the majority
+    await initializeRegistry(provider.wallet.publicKey).rpc({ commitment: "confirmed" });
+    
+    for (const juror of jurors) {
+      const account = await createAssociatedTokenAccount(provider.connection, juror, governanceMint, juror.publicKey);
+      await mintTo(provider.connection, authority, governanceMint, account, authority, 1_000);
+      jurorTokens.push(account);
+      
+      await program.methods
+        .stakeJuror(new anchor.BN(1_000))
+        .accounts({
+          juror: juror.publicKey,
+          registry,
+          governanceMint,
+          stakeVault,
+          jurorTokens: account,
+          governanceTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([juror])
+        .rpc({ commitment: "confirmed" });
+    }
+    assert.equal((await balance(stakeVault)).toString(), "4000");
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Jury pool",
+      description: "Disputes go to a jury",
+    });
+    
+    await program.methods
+      .configureBonds(new anchor.BN(1_000_000), new anchor.BN(500_000))
+      .accounts({
+        authority: authority.publicKey,
+        pool: poolPda,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .enableJuryResolution()
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .mintPredictionTokens(new anchor.BN(1_000_000), NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault,
+        userCollateral: disputerCollateral,
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("The authority cannot resolve a jury dispute itself", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    await program.methods
+      .disputeSolution(NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        outcomeMint: noMint,
+        userOutcomeToken: disputerNo,
+        collateralMint,
+        bondEscrow,
+        userCollateral: disputerCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+    
+    try {
+      await program.methods
+        .resolveDispute(YES)
+        .accounts({
+          authority: authority.publicKey,
+          pool: poolPda,
+          collateralMint,
+          bondEscrow,
+          authorityCollateral,
+          disputerCollateral,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("A jury pool should not be resolved by its authority");
+    } catch (error) {
+      assert.include(error.toString(), "JuryResolutionEnabled");
+    }
+  });
+
+  it("A jury is drawn and its stakes are locked", async () => {
+    // The seed is the hash of a slot after the dispute, which has to land first
+    const { disputeSlot } = await program.account.bettingPool.fetch(poolPda);
+    while ((await provider.connection.getSlot("confirmed")) <= disputeSlot.toNumber() + JURY_DRAW_DELAY_SLOTS + 1) {
+      await sleep(400);
+    }
+    await program.methods
+      .drawJury()
+      .accounts({
+        payer: disputer.publicKey,
+        pool: poolPda,
+        registry,
+        panel,
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+    
+    const panelData = await program.account.juryPanel.fetch(panel);
+    assert.equal(panelData.seats.length, 3);
+    const drawn = panelData.seats.map((seat) => jurorFor(seat.juror));
+    assert.notInclude(drawn, -1);
+    
+    const juror = drawn[0];
+    try {
+      await program.methods
+        .unstakeJuror(new anchor.BN(1_000))
+        .accounts({
+          juror: jurors[juror].publicKey,
+          registry,
+          governanceMint,
+          stakeVault,
+          jurorTokens: jurorTokens[juror],
+          governanceTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([jurors[juror]])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("A drawn juror should not be able to unstake");
+    } catch (error) {
+      assert.include(error.toString(), "JurorStakeLocked");
+    }
+  });
+
+  it("The jury overturns the proposal by majority", async () => {
+    const panelData = await program.account.juryPanel.fetch(panel);
+    const drawn = panelData.seats.map((seat) => jurors[jurorFor(seat.juror)]);
+    const votes = [NO, NO, YES];
+    const salts = drawn.map(() => randomBytes(32));
+    
+    for (let seat = 0; seat < drawn.length; seat++) {
+      await program.methods
+        .commitJuryVote(commitment(drawn[seat].publicKey, votes[seat], salts[seat]))
+        .accounts({ juror: drawn[seat].publicKey, pool: poolPda, panel })
+        .signers([drawn[seat]])
+        .rpc({ commitment: "confirmed" });
+    }
+    
+    await waitUntil(provider.connection, panelData.commitDeadline.toNumber());
+    for (let seat = 0; seat < drawn.length; seat++) {
+      await program.methods
+        .revealJuryVote(votes[seat], [...salts[seat]])
+        .accounts({ juror: drawn[seat].publicKey, pool: poolPda, panel })
+        .signers([drawn[seat]])
+        .rpc({ commitment: "confirmed" });
+    }
+    
+    await waitUntil(provider.connection, panelData.revealDeadline.toNumber());
+    const disputerBefore = await balance(disputerCollateral);
+    await program.methods
+      .tallyJuryVotes()
+      .accounts({
+        pool: poolPda,
+        registry,
+        panel,
+        payer: disputer.publicKey,
+        collateralMint,
+        bondEscrow,
+        authorityCollateral,
+        disputerCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.solutionWinner, NO);
+    assert.isFalse(poolData.isDisputed);
+    
+    // The disputer gets its bond back plus the proposer's bond
+    const disputerAfter = await balance(disputerCollateral);
+    assert.equal((disputerAfter - disputerBefore).toString(), "1500000");
+    
+    // The YES juror loses 100, split between the two NO jurors
+    assert.equal(await stakeOf(drawn[0]), 1_050);
+    assert.equal(await stakeOf(drawn[1]), 1_050);
+    assert.equal(await stakeOf(drawn[2]), 900);
+    assert.isNull(await provider.connection.getAccountInfo(panel));
+  });
+
+  it("Jurors can unstake once the panel is tallied", async () => {
+    const [first] = jurors;
+    const stake = await stakeOf(first);
+    
+    await program.methods
+      .unstakeJuror(new anchor.BN(stake))
+      .accounts({
+        juror: first.publicKey,
+        registry,
+        governanceMint,
+        stakeVault,
+        jurorTokens: jurorTokens[0],
+        governanceTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([first])
+      .rpc({ commitment: "confirmed" });
+    
+    assert.equal((await balance(jurorTokens[0])).toString(), stake.toString());
+    const registryData = await program.account.juryRegistry.fetch(registry);
+    assert.equal(registryData.jurors.length, jurors.length - 1);
+  });
+
+  it("A dispute without enough eligible jurors cannot be drawn or resolved early", async () => {
+    // Two jurors are left once the operator, itself a juror, is excluded
+    const operator = jurors[1];
+    const otherMarketId = new anchor.BN(62);
+    const otherPool = findPoolPda(program.programId, operator.publicKey, otherMarketId);
+    const otherNoMint = findOutcomeMintPda(program.programId, otherPool, NO);
+    const [otherPanel] = PublicKey.findProgramAddressSync([Buffer.from("jury_panel"), otherPool.toBuffer()], program.programId);
+    const operatorCollateral = await createAssociatedTokenAccount(provider.connection, operator, collateralMint, operator.publicKey);
+    
+    const otherEndTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, operator, otherMarketId, collateralMint, {
+      endTime: otherEndTime,
+      disputeThreshold: 0,
+      name: "Jury pool",
+      description: "Too few jurors for a panel",
+    });
+    await program.methods
+      .enableJuryResolution()
+      .accounts({ authority: operator.publicKey, pool: otherPool })
+      .signers([operator])
+      .rpc({ commitment: "confirmed" });
+    
+    await waitUntil(provider.connection, otherEndTime);
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: operator.publicKey, pool: otherPool })
+      .signers([operator])
+      .rpc({ commitment: "confirmed" });
+    const otherDisputerNo = await createAssociatedTokenAccount(
+      provider.connection,
+      disputer,
+      otherNoMint,
+      disputer.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .disputeSolution(NO)
+      .accounts({
+        user: disputer.publicKey,
+        pool: otherPool,
+        config: findConfigPda(program.programId),
+        outcomeMint: otherNoMint,
+        userOutcomeToken: otherDisputerNo,
+        collateralMint,
+        bondEscrow: findBondEscrowPda(program.programId, otherPool),
+        userCollateral: disputerCollateral,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([disputer])
+      .rpc({ commitment: "confirmed" });
+    
+    const { disputeSlot } = await program.account.bettingPool.fetch(otherPool);
+    while ((await provider.connection.getSlot("confirmed")) <= disputeSlot.toNumber() + JURY_DRAW_DELAY_SLOTS + 1) {
+      await sleep(400);
+    }
+    try {
+      await program.methods
+        .drawJury()
+        .accounts({
+          payer: disputer.publicKey,
+          pool: otherPool,
+          registry,
+          panel: otherPanel,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([disputer])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Two eligible jurors cannot fill a panel of three");
+    } catch (error) {
+      assert.include(error.toString(), "NotEnoughJurors");
+    }
+    
+    // The operator only takes over once the draw timeout has passed, see jury-timeout.ts
+    try {
+      await program.methods
+        .resolveDispute(YES)
+        .accounts({
+          authority: operator.publicKey,
+          pool: otherPool,
+          collateralMint,
+          bondEscrow: findBondEscrowPda(program.programId, otherPool),
+          authorityCollateral: operatorCollateral,
+          disputerCollateral,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([operator])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("The operator should wait for the draw timeout");
+    } catch (error) {
+      assert.include(error.toString(), "JuryResolutionEnabled");
+    }
+  });
+});
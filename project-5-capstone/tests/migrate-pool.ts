@@ -0,0 +1,71 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+  Transaction,
+  TransactionInstruction,
+  LAMPORTS_PER_SOL,
+} from '@solana/web3.js';
+import { LiteSVM, FailedTransactionMetadata } from "litesvm";
+import { assert } from "chai";
+import * as fs from "fs";
+import * as path from "path";
+
+// Pools created before versioning are loaded from a fixture written by that
+// program's own `BettingPool` type, since the current program can no longer create them
+describe("pool migration", () => {
+  const idl = JSON.parse(fs.readFileSync(path.join(__dirname, "../target/idl/project_5_capstone.json"), "utf8"));
+  const fixture = JSON.parse(fs.readFileSync(path.join(__dirname, "fixtures/baseline-pool.json"), "utf8"));
+
+  const programId = new PublicKey(idl.address);
+  const coder = new anchor.BorshCoder(idl);
+  const poolPda = new PublicKey(fixture.pubkey);
+  const baselineData = Buffer.from(fixture.account.data[0], "base64");
+  const payer = Keypair.generate();
+
+  let svm: LiteSVM;
+
+  const migratePool = () => {
+    const tx = new Transaction().add(
+      new TransactionInstruction({
+        programId,
+        keys: [{ pubkey: poolPda, isSigner: false, isWritable: true }],
+        data: coder.instruction.encode("migrate_pool", {}),
+      })
+    );
+    tx.recentBlockhash = svm.latestBlockhash();
+    tx.feePayer = payer.publicKey;
+    tx.sign(payer);
+    return svm.sendTransaction(tx);
+  };
+
+  before(() => {
+    svm = new LiteSVM();
+    svm.addProgramFromFile(programId, path.join(__dirname, "../target/deploy/project_5_capstone.so"));
+    svm.airdrop(payer.publicKey, BigInt(LAMPORTS_PER_SOL));
+    svm.setAccount(poolPda, {
+      lamports: fixture.account.lamports,
+      data: baselineData,
+      owner: programId,
+      executable: false,
+    });
+  });
+
+  it("The baseline layout does not load as a current pool", () => {
+    assert.throws(() => coder.accounts.decode("BettingPool", baselineData));
+    
+    // Baseline pools sit at a PDA without the market id
+    const authority = new PublicKey(baselineData.subarray(8, 40));
+    const [expectedPool] = PublicKey.findProgramAddressSync([Buffer.from("pool"), authority.toBuffer()], programId);
+    assert.ok(expectedPool.equals(poolPda));
+  });
+
+  it("Baseline pools are rejected rather than migrated", () => {
+    const result = migratePool();
+    assert.instanceOf(result, FailedTransactionMetadata);
+    assert.include((result as FailedTransactionMetadata).meta().logs().join("\n"), "LegacyPoolUnsupported");
+    
+    const account = svm.getAccount(poolPda);
+    assert.ok(Buffer.from(account.data).equals(baselineData), "The account is left as it was");
+  });
+});
@@ -2,42 +2,53 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { 
   PublicKey, 
-  SystemProgram, 
   Keypair,
   LAMPORTS_PER_SOL
 } from '@solana/web3.js';
 import { 
-  TOKEN_PROGRAM_ID, 
   TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
 } from '@solana/spl-token';
 import { assert } from "chai";
+import { ensureConfig, initializePool } from "./helpers";
 
 describe("project-5-capstone-minimal", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
 
   const provider = anchor.getProvider() as anchor.AnchorProvider;
   const program = anchor.workspace.Project5Capstone as Program<any>;
-  
+
   const authority = Keypair.generate();
-  const yesMint = Keypair.generate();
-  const noMint = Keypair.generate();
-  
-  // PDA for the pool
+
+  // PDA for the pool, one per (authority, market id)
+  const marketId = new anchor.BN(1);
   const [poolPda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("pool"), authority.publicKey.toBuffer()],
+    [Buffer.from("pool"), authority.publicKey.toBuffer(), marketId.toArrayLike(Buffer, "le", 8)],
     program.programId
   );
-  
+
+  let collateralMint: PublicKey;
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
   it("Airdrop SOL to authority", async () => {
     const authorityAirdrop = await provider.connection.requestAirdrop(
       authority.publicKey,
       2 * LAMPORTS_PER_SOL
     );
     await provider.connection.confirmTransaction(authorityAirdrop);
+    
+    collateralMint = await createMint(
+      provider.connection,
+      authority,
+      authority.publicKey,
+      null,
+      6
+    );
     console.log(`Authority: ${authority.publicKey.toString()}`);
-    console.log(`Yes Mint: ${yesMint.publicKey.toString()}`);
-    console.log(`No Mint: ${noMint.publicKey.toString()}`);
     console.log(`Pool PDA: ${poolPda.toString()}`);
   });
 
@@ -52,25 +63,13 @@ describe("project-5-capstone-minimal", () => {
     const endTime = currentTime + 3600;
     
     try {
-      await program.methods
-        .initializePool(
-          new anchor.BN(disputePeriodSeconds),
-          new anchor.BN(disputeThreshold),
-          poolName,
-          poolDescription,
-          new anchor.BN(endTime)
-        )
-        .accounts({
-          authority: authority.publicKey,
-          pool: poolPda,
-          yes_mint: yesMint.publicKey,
-          no_mint: noMint.publicKey,
-          token_program: TOKEN_2022_PROGRAM_ID,
-          system_program: SystemProgram.programId,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        })
-        .signers([authority, yesMint, noMint])
-        .rpc({ commitment: "confirmed" });
+      await initializePool(program, authority, marketId, collateralMint, {
+        endTime,
+        disputePeriodSeconds,
+        disputeThreshold,
+        name: poolName,
+        description: poolDescription,
+      });
       
       // Fetch the pool data to verify
       const poolData = await program.account.bettingPool.fetch(poolPda);
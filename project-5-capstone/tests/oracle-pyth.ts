@@ -0,0 +1,177 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { startAnchor, Clock, ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import {
+  PublicKey,
+  Keypair,
+} from '@solana/web3.js';
+import { assert } from "chai";
+import {
+  findPoolPda,
+  mintAccount,
+  createPool,
+  setConfig,
+} from "./helpers";
+
+const IDL = require("../target/idl/project_5_capstone.json");
+
+const PYTH_RECEIVER_PROGRAM_ID = new PublicKey("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+// sha256("account:PriceUpdateV2")[..8]
+const PRICE_UPDATE_V2_DISCRIMINATOR = Buffer.from([34, 241, 35, 99, 157, 126, 244, 205]);
+
+// A fully verified PriceUpdateV2 account, laid out the way the Pyth receiver writes it
+function priceUpdateAccount(price: bigint, conf: bigint, publishTime: number) {
+  const data = Buffer.alloc(134);
+  let offset = PRICE_UPDATE_V2_DISCRIMINATOR.copy(data, 0);
+  offset += 32; // write_authority
+  offset = data.writeUInt8(1, offset); // VerificationLevel::Full
+  offset += 32; // feed_id
+  offset = data.writeBigInt64LE(price, offset);
+  offset = data.writeBigUInt64LE(conf, offset);
+  offset = data.writeInt32LE(-8, offset);
+  offset = data.writeBigInt64LE(BigInt(publishTime), offset);
+  return {
+    lamports: 1_000_000_000,
+    data,
+    owner: PYTH_RECEIVER_PROGRAM_ID,
+    executable: false,
+  };
+}
+
+describe("pyth oracle resolution", () => {
+  const collateralMint = Keypair.generate().publicKey;
+  const priceFeed = Keypair.generate().publicKey;
+  const marketId = new anchor.BN(41);
+  const fallbackMarketId = new anchor.BN(42);
+  const strike = new anchor.BN(100_00000000); // $100 at exponent -8
+
+  let context: ProgramTestContext;
+  let program: Program<any>;
+  let authority: PublicKey;
+  let poolPda: PublicKey;
+  let fallbackPool: PublicKey;
+  let endTime: number;
+
+  // Move the bank clock, and the slot with it so repeated transactions are not deduplicated
+  const setTime = async (unixTimestamp: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(clock.slot + 1n, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp))
+    );
+  };
+
+  const resolve = (feed = priceFeed) =>
+    program.methods
+      .resolveFromOracle()
+      .accounts({ pool: poolPda, priceFeed: feed })
+      .rpc();
+  
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      assert.fail(`Expected ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  };
+
+  before(async () => {
+    context = await startAnchor(".", [], [{ address: collateralMint, info: mintAccount(6) }]);
+    const provider = new BankrunProvider(context);
+    program = new Program(IDL, provider);
+    await setConfig(context, program);
+    authority = context.payer.publicKey;
+    poolPda = findPoolPda(program.programId, authority, marketId);
+    fallbackPool = findPoolPda(program.programId, authority, fallbackMarketId);
+    
+    const clock = await context.banksClient.getClock();
+    endTime = Number(clock.unixTimestamp) + 100;
+  });
+
+  it("Creates pools resolved by a Pyth feed", async () => {
+    for (const id of [marketId, fallbackMarketId]) {
+      await createPool(program, context.payer, id, collateralMint, {
+        endTime,
+        resolutionSource: {
+          oracle: {
+            provider: { pyth: {} },
+            priceFeed,
+            strike,
+            comparison: { above: {} },
+            maxStalenessSeconds: new anchor.BN(60),
+            maxConfidenceBps: new anchor.BN(100),
+          },
+        },
+        disputePeriodSeconds: 60,
+        name: "SOL above $100",
+        description: "Will SOL trade above $100 at the close?",
+      });
+    }
+  });
+
+  it("Cannot resolve before end_time", async () => {
+    context.setAccount(priceFeed, priceUpdateAccount(105_00000000n, 1_000000n, endTime - 10));
+    await expectError(resolve(), "BettingPeriodNotEnded");
+  });
+
+  it("The authority cannot propose an answer", async () => {
+    await setTime(endTime + 30);
+    await expectError(
+      program.methods.proposeSolution(1).accounts({ authority, pool: poolPda }).rpc(),
+      "ManualResolutionDisabled"
+    );
+  });
+
+  it("Rejects prices published before end_time", async () => {
+    await expectError(resolve(), "OraclePriceTooEarly");
+  });
+
+  it("Rejects a feed other than the pool's", async () => {
+    const otherFeed = Keypair.generate().publicKey;
+    context.setAccount(otherFeed, priceUpdateAccount(105_00000000n, 1_000000n, endTime + 1));
+    await expectError(resolve(otherFeed), "InvalidOracleAccount");
+  });
+
+  it("Rejects a wide confidence interval", async () => {
+    // 2% of the price against a 1% limit
+    context.setAccount(priceFeed, priceUpdateAccount(105_00000000n, 2_10000000n, endTime + 1));
+    await expectError(resolve(), "OracleConfidenceTooWide");
+  });
+
+  it("Rejects a price published long after end_time", async () => {
+    context.setAccount(priceFeed, priceUpdateAccount(105_00000000n, 1_000000n, endTime + 400));
+    await setTime(endTime + 410);
+    await expectError(resolve(), "OraclePriceTooLate");
+  });
+
+  it("Rejects a price published after the staleness window", async () => {
+    context.setAccount(priceFeed, priceUpdateAccount(105_00000000n, 1_000000n, endTime + 90));
+    await expectError(resolve(), "StaleOraclePrice");
+  });
+
+  it("Anyone resolves YES from a price above the strike, long after the close", async () => {
+    // Freshness is measured from end_time, not from when the pool is resolved
+    context.setAccount(priceFeed, priceUpdateAccount(105_00000000n, 1_000000n, endTime + 30));
+    await setTime(endTime + 3_600);
+    await resolve();
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.solutionWinner, 0);
+    assert.isTrue(poolData.isFinalized);
+  });
+
+  it("The authority answers an oracle pool nobody resolved once the timeout passes", async () => {
+    const propose = () =>
+      program.methods.proposeSolution(1).accounts({ authority, pool: fallbackPool }).rpc();
+    await expectError(propose(), "ManualResolutionDisabled");
+    
+    // Seven days after the close the pool falls back to manual proposals
+    await setTime(endTime + 7 * 24 * 60 * 60);
+    await propose();
+    
+    const poolData = await program.account.bettingPool.fetch(fallbackPool);
+    assert.equal(poolData.solutionWinner, 1);
+    assert.isFalse(poolData.isFinalized, "The fallback answer goes through the dispute window");
+  });
+});
@@ -0,0 +1,139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { startAnchor, Clock, ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import {
+  PublicKey,
+  Keypair,
+} from '@solana/web3.js';
+import { assert } from "chai";
+import {
+  findPoolPda,
+  mintAccount,
+  createPool,
+  setConfig,
+} from "./helpers";
+
+const IDL = require("../target/idl/project_5_capstone.json");
+
+const SWITCHBOARD_PROGRAM_ID = new PublicKey("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+// sha256("account:PullFeedAccountData")[..8]
+const PULL_FEED_DISCRIMINATOR = Buffer.from([196, 27, 108, 196, 10, 215, 219, 40]);
+const PULL_FEED_SIZE = 8 + 3200;
+const RESULT_OFFSET = 8 + 2256;
+const TIMESTAMPS_OFFSET = 8 + 2944;
+const ONE = BigInt(10) ** BigInt(18);
+
+function writeI128(data: Buffer, value: bigint, offset: number) {
+  data.writeBigUInt64LE(value & ((BigInt(1) << BigInt(64)) - BigInt(1)), offset);
+  data.writeBigInt64LE(value >> BigInt(64), offset + 8);
+}
+
+// A pull feed whose current result is `value` (18 decimals), landed at `publishTime`
+function pullFeedAccount(value: bigint, stdDev: bigint, publishTime: number, owner = SWITCHBOARD_PROGRAM_ID) {
+  const submissionIdx = 3;
+  const data = Buffer.alloc(PULL_FEED_SIZE);
+  PULL_FEED_DISCRIMINATOR.copy(data, 0);
+  writeI128(data, value, RESULT_OFFSET); // result.value
+  writeI128(data, stdDev, RESULT_OFFSET + 16); // result.std_dev
+  data.writeUInt8(submissionIdx, RESULT_OFFSET + 97); // result.submission_idx
+  data.writeBigUInt64LE(BigInt(1000), RESULT_OFFSET + 104); // result.slot
+  data.writeBigInt64LE(BigInt(publishTime), TIMESTAMPS_OFFSET + submissionIdx * 8);
+  return { lamports: 1_000_000_000, data, owner, executable: false };
+}
+
+describe("switchboard oracle resolution", () => {
+  const collateralMint = Keypair.generate().publicKey;
+  const priceFeed = Keypair.generate().publicKey;
+  const marketId = new anchor.BN(42);
+
+  let context: ProgramTestContext;
+  let program: Program<any>;
+  let authority: PublicKey;
+  let poolPda: PublicKey;
+  let endTime: number;
+
+  const setTime = async (unixTimestamp: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(clock.slot + 1n, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp))
+    );
+  };
+
+  const resolve = () =>
+    program.methods
+      .resolveFromOracle()
+      .accounts({ pool: poolPda, priceFeed })
+      .rpc();
+  
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      assert.fail(`Expected ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  };
+
+  before(async () => {
+    context = await startAnchor(".", [], [{ address: collateralMint, info: mintAccount(6) }]);
+    const provider = new BankrunProvider(context);
+    program = new Program(IDL, provider);
+    await setConfig(context, program);
+    authority = context.payer.publicKey;
+    poolPda = findPoolPda(program.programId, authority, marketId);
+    
+    const clock = await context.banksClient.getClock();
+    endTime = Number(clock.unixTimestamp) + 100;
+  });
+
+  it("Creates a scalar pool resolved by a Switchboard feed", async () => {
+    // Bounds and value in cents: $0 to $200
+    await createPool(program, context.payer, marketId, collateralMint, {
+      endTime,
+      marketKind: { scalar: { lowerBound: new anchor.BN(0), upperBound: new anchor.BN(200_00) } },
+      resolutionSource: {
+        oracle: {
+          provider: { switchboard: { decimals: 2 } },
+          priceFeed,
+          strike: new anchor.BN(0),
+          comparison: { above: {} },
+          maxStalenessSeconds: new anchor.BN(60),
+          maxConfidenceBps: new anchor.BN(50),
+        },
+      },
+      disputePeriodSeconds: 60,
+      name: "SOL close",
+      description: "Where will SOL close between $0 and $200?",
+      labels: ["LONG", "SHORT"],
+    });
+  });
+
+  it("Rejects a feed not owned by Switchboard", async () => {
+    await setTime(endTime + 30);
+    context.setAccount(priceFeed, pullFeedAccount(BigInt(150) * ONE, BigInt(0), endTime + 10, Keypair.generate().publicKey));
+    await expectError(resolve(), "InvalidOracleAccount");
+  });
+
+  it("Rejects a result whose submissions disagree too much", async () => {
+    // A $1 standard deviation on $150 is above the 0.5% limit
+    context.setAccount(priceFeed, pullFeedAccount(BigInt(150) * ONE, ONE, endTime + 10));
+    await expectError(resolve(), "OracleConfidenceTooWide");
+  });
+
+  it("Rejects a result landed too long after the close", async () => {
+    context.setAccount(priceFeed, pullFeedAccount(BigInt(150) * ONE, BigInt(0), endTime + 70));
+    await setTime(endTime + 80);
+    await expectError(resolve(), "StaleOraclePrice");
+  });
+
+  it("Resolves to the feed value rescaled to cents", async () => {
+    // $150.257 truncates to 15025 cents
+    context.setAccount(priceFeed, pullFeedAccount(BigInt(150_257) * ONE / BigInt(1000), ONE / BigInt(100), endTime + 10));
+    await resolve();
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.solutionValue.toNumber(), 150_25);
+    assert.isTrue(poolData.isFinalized);
+  });
+});
@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  getTokenMetadata,
+  getMetadataPointerState,
+  getMint,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  findPoolPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("outcome metadata", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  const marketId = new anchor.BN(161);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+
+  let collateralMint: PublicKey;
+
+  const updateUri = (signer: Keypair, outcome: number, outcomeMint: PublicKey, uri: string) =>
+    program.methods
+      .updateOutcomeUri(outcome, uri)
+      .accounts({
+        authority: signer.publicKey,
+        pool: poolPda,
+        outcomeMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Outcome mints are named after the pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, stranger.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime: Math.floor(Date.now() / 1000) + 300,
+      disputePeriodSeconds: 60,
+      name: "Rain tomorrow",
+      description: "Wallets show the outcome names",
+      uri: (label) => `https://example.com/rain/${label.toLowerCase()}.json`,
+    });
+    
+    const mint = await getMint(provider.connection, yesMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.ok(getMetadataPointerState(mint).metadataAddress.equals(yesMint), "The mint holds its own metadata");
+    
+    const yes = await getTokenMetadata(provider.connection, yesMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(yes.name, "Rain tomorrow YES");
+    assert.equal(yes.symbol, "YES");
+    assert.equal(yes.uri, "https://example.com/rain/yes.json");
+    assert.ok(yes.updateAuthority.equals(poolPda));
+    
+    const no = await getTokenMetadata(provider.connection, noMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(no.name, "Rain tomorrow NO");
+  });
+
+  it("Only the operator can update the URI", async () => {
+    try {
+      await updateUri(stranger, YES, yesMint, "https://example.com/spoofed.json");
+      assert.fail("Strangers cannot change the metadata");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+  });
+
+  it("The URI can grow, with the operator paying the extra rent", async () => {
+    const uri = "https://example.com/markets/weather/rain-tomorrow/outcomes/yes/metadata.json";
+    await updateUri(authority, YES, yesMint, uri);
+    assert.equal((await getTokenMetadata(provider.connection, yesMint, "confirmed", TOKEN_2022_PROGRAM_ID)).uri, uri);
+    
+    try {
+      await updateUri(authority, YES, yesMint, "x".repeat(201));
+      assert.fail("URIs are capped");
+    } catch (error) {
+      assert.include(error.toString(), "OutcomeUriTooLong");
+    }
+  });
+});
@@ -0,0 +1,254 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("pause", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const guardian = Keypair.generate();
+  const user = Keypair.generate();
+
+  const marketId = new anchor.BN(111);
+  const config = findConfigPda(program.programId);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const userYes = getAssociatedTokenAddressSync(yesMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let userCollateral: PublicKey;
+  let authorityCollateral: PublicKey;
+  let endTime: number;
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      assert.fail(`Expected ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  };
+
+  const mint = () =>
+    program.methods
+      .mintPredictionTokens(new anchor.BN(1_000_000), YES)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral,
+        outcomeMint: yesMint,
+        userOutcomeToken: userYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+  
+  const claim = () =>
+    program.methods
+      .claimWinnings()
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral,
+        winningMint: yesMint,
+        userWinningToken: userYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+  
+  const withdrawFees = () =>
+    program.methods
+      .withdrawFees()
+      .accounts({
+        recipient: authority.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        recipientCollateral: authorityCollateral,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  const finalize = () =>
+    program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  // With no pool the guardian acts on every pool at once
+  const guardianCall = (method: string, pool: PublicKey | null) =>
+    program.methods[method]()
+      .accounts({ guardian: guardian.publicKey, config, pool })
+      .signers([guardian])
+      .rpc({ commitment: "confirmed" });
+  
+  const unpauseAt = async (pool: PublicKey | null) => {
+    const pause = pool
+      ? (await program.account.bettingPool.fetch(pool)).pause
+      : (await program.account.globalConfig.fetch(config)).pause;
+    return pause.unpauseAt.toNumber();
+  };
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  // Hand the guardian role back to the admin with the default delay
+  after(async () => {
+    await program.methods
+      .setGuardian(provider.wallet.publicKey, new anchor.BN(24 * 60 * 60))
+      .accounts({ admin: provider.wallet.publicKey, config })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Set up a guardian with a short unpause delay and an open pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, guardian.publicKey, 1);
+    await airdrop(provider.connection, user.publicKey, 1);
+    
+    await program.methods
+      .setGuardian(guardian.publicKey, new anchor.BN(3))
+      .accounts({ admin: provider.wallet.publicKey, config })
+      .rpc({ commitment: "confirmed" });
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    userCollateral = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+    authorityCollateral = await createAssociatedTokenAccount(provider.connection, authority, collateralMint, authority.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, userCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 30;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Pausable pool",
+      description: "Trading can be halted by the guardian",
+    });
+    
+    await mint();
+  });
+
+  it("Only the guardian can pause", async () => {
+    await expectError(
+      program.methods
+        .pause()
+        .accounts({ guardian: authority.publicKey, config, pool: poolPda })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" }),
+      "Unauthorized"
+    );
+  });
+
+  it("A paused pool rejects mints until its unpause delay passes", async () => {
+    await guardianCall("pause", poolPda);
+    await expectError(mint(), "PoolPaused");
+    
+    // Unpausing needs a request first, then the delay
+    await expectError(guardianCall("unpause", poolPda), "UnpauseTimelocked");
+    await guardianCall("requestUnpause", poolPda);
+    await expectError(guardianCall("unpause", poolPda), "UnpauseTimelocked");
+    
+    await waitUntil(provider.connection, await unpauseAt(poolPda));
+    await guardianCall("unpause", poolPda);
+    await mint();
+  });
+
+  it("The global pause halts every pool", async () => {
+    await guardianCall("pause", null);
+    await expectError(mint(), "ProtocolPaused");
+    
+    await guardianCall("requestUnpause", null);
+    await waitUntil(provider.connection, await unpauseAt(null));
+    await guardianCall("unpause", null);
+    await mint();
+    
+    assert.equal(
+      (await getAccount(provider.connection, userYes, "confirmed", TOKEN_2022_PROGRAM_ID)).amount.toString(),
+      "3000000"
+    );
+  });
+
+  it("A paused pool cannot be finalized", async () => {
+    await waitUntil(provider.connection, endTime);
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, poolData.disputePeriodEnd.toNumber());
+    
+    await guardianCall("pause", poolPda);
+    await expectError(finalize(), "PoolPaused");
+    await guardianCall("requestUnpause", poolPda);
+    await waitUntil(provider.connection, await unpauseAt(poolPda));
+    await guardianCall("unpause", poolPda);
+    
+    await guardianCall("pause", null);
+    await expectError(finalize(), "ProtocolPaused");
+    await guardianCall("requestUnpause", null);
+    await waitUntil(provider.connection, await unpauseAt(null));
+    await guardianCall("unpause", null);
+    
+    await finalize();
+    assert.isTrue((await program.account.bettingPool.fetch(poolPda)).isFinalized);
+  });
+
+  it("Claims and fee withdrawals wait out a pause", async () => {
+    await guardianCall("pause", poolPda);
+    await expectError(claim(), "PoolPaused");
+    await expectError(withdrawFees(), "PoolPaused");
+    
+    await guardianCall("requestUnpause", poolPda);
+    await waitUntil(provider.connection, await unpauseAt(poolPda));
+    await guardianCall("unpause", poolPda);
+    await claim();
+    // 7M left after the three mints, plus the whole pot less any protocol fee
+    assert.isAbove(Number((await getAccount(provider.connection, userCollateral)).amount), 7_000_000);
+  });
+});
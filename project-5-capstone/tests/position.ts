@@ -0,0 +1,224 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  transferChecked,
+  createAssociatedTokenAccountIdempotent,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("positions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+  const other = Keypair.generate();
+
+  const marketId = new anchor.BN(141);
+  const config = findConfigPda(program.programId);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const noMint = findOutcomeMintPda(program.programId, poolPda, NO);
+  const userYes = getAssociatedTokenAddressSync(yesMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const otherYes = getAssociatedTokenAddressSync(yesMint, other.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const userNo = getAssociatedTokenAddressSync(noMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let userCollateral: PublicKey;
+  let otherCollateral: PublicKey;
+  let endTime: number;
+
+  const findPositionPda = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), poolPda.toBuffer(), owner.toBuffer()],
+      program.programId
+    )[0];
+  
+  const fetchPosition = (owner: PublicKey) => program.account.position.fetch(findPositionPda(owner));
+
+  const mint = (owner: Keypair, ownerCollateral: PublicKey, amount: number, outcome: number, outcomeMint: PublicKey, ownerOutcome: PublicKey) =>
+    program.methods
+      .mintPredictionTokens(new anchor.BN(amount), outcome)
+      .accounts({
+        user: owner.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral: ownerCollateral,
+        outcomeMint,
+        userOutcomeToken: ownerOutcome,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  
+  const claim = (owner: Keypair, ownerCollateral: PublicKey, ownerYes: PublicKey) =>
+    program.methods
+      .claimWinnings()
+      .accounts({
+        user: owner.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral: ownerCollateral,
+        winningMint: yesMint,
+        userWinningToken: ownerYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up a pool", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, user.publicKey, 1);
+    await airdrop(provider.connection, other.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    userCollateral = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+    otherCollateral = await createAssociatedTokenAccount(provider.connection, other, collateralMint, other.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, userCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, otherCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      name: "Tracked pool",
+      description: "Positions record every trade",
+    });
+  });
+
+  it("The first trade opens a position that records deposits and burns", async () => {
+    await mint(user, userCollateral, 3_000_000, YES, yesMint, userYes);
+    await mint(user, userCollateral, 1_000_000, NO, noMint, userNo);
+    
+    await program.methods
+      .burnPredictionTokens(new anchor.BN(1_000_000), NO)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config,
+        collateralMint,
+        collateralVault,
+        userCollateral,
+        outcomeMint: noMint,
+        userOutcomeToken: userNo,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    
+    const position = await fetchPosition(user.publicKey);
+    assert.ok(position.pool.equals(poolPda));
+    assert.ok(position.user.equals(user.publicKey));
+    assert.equal(position.deposited.toNumber(), 4_000_000);
+    assert.equal(position.withdrawn.toNumber(), 1_000_000);
+    assert.equal(position.minted[YES].toNumber(), 3_000_000);
+    assert.equal(position.minted[NO].toNumber(), 1_000_000);
+    assert.equal(position.burned[NO].toNumber(), 1_000_000);
+    assert.equal(position.claimed.toNumber(), 0);
+    assert.equal(position.claimedOutcomes, 0);
+    
+    await mint(other, otherCollateral, 2_000_000, NO, noMint, getAssociatedTokenAddressSync(noMint, other.publicKey, false, TOKEN_2022_PROGRAM_ID));
+    await mint(other, otherCollateral, 1_000_000, YES, yesMint, otherYes);
+  });
+
+  it("A claim records the collateral paid out", async () => {
+    await waitUntil(provider.connection, endTime);
+    await program.methods
+      .proposeSolution(YES)
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    await waitUntil(provider.connection, poolData.disputePeriodEnd.toNumber());
+    await program.methods
+      .finalizePool()
+      .accounts({ user: user.publicKey, pool: poolPda })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    
+    await claim(user, userCollateral, userYes);
+    
+    // 6M pot, 3M of the 4M winning tokens are the user's
+    const position = await fetchPosition(user.publicKey);
+    assert.equal(position.claimed.toNumber(), 4_500_000);
+    assert.equal(position.claimedOutcomes, 1 << YES);
+  });
+
+  it("Winning tokens topped up after a claim cannot be claimed again", async () => {
+    // The claim closed the user's emptied token account
+    await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      user,
+      yesMint,
+      user.publicKey,
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      provider.connection,
+      other,
+      otherYes,
+      yesMint,
+      userYes,
+      other,
+      1_000_000,
+      6,
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+    
+    try {
+      await claim(user, userCollateral, userYes);
+      assert.fail("A second claim of the same outcome should fail");
+    } catch (error) {
+      assert.include(error.toString(), "AlreadyClaimed");
+    }
+    assert.equal((await getAccount(provider.connection, userYes, "confirmed", TOKEN_2022_PROGRAM_ID)).amount.toString(), "1000000");
+    assert.equal((await fetchPosition(user.publicKey)).claimed.toNumber(), 4_500_000);
+  });
+});
@@ -0,0 +1,184 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  createPool,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const LONG = 0;
+const SHORT = 1;
+
+describe("scalar market", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+
+  const authority = Keypair.generate();
+  const longUser = Keypair.generate();
+  const shortUser = Keypair.generate();
+
+  const marketId = new anchor.BN(11);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const collateralVault = findVaultPda(program.programId, poolPda);
+  const longMint = findOutcomeMintPda(program.programId, poolPda, LONG);
+  const shortMint = findOutcomeMintPda(program.programId, poolPda, SHORT);
+  const longToken = getAssociatedTokenAddressSync(longMint, longUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const shortToken = getAssociatedTokenAddressSync(shortMint, shortUser.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let longCollateral: PublicKey;
+  let shortCollateral: PublicKey;
+  let endTime: number;
+
+  const claimAccounts = (user: Keypair, userCollateral: PublicKey, winningMint: PublicKey, userWinningToken: PublicKey) => ({
+    user: user.publicKey,
+    pool: poolPda,
+    config: findConfigPda(program.programId),
+    collateralMint,
+    collateralVault,
+    userCollateral,
+    winningMint,
+    userWinningToken,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    collateralTokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Set up collateral and users", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, longUser.publicKey, 1);
+    await airdrop(provider.connection, shortUser.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    longCollateral = await createAssociatedTokenAccount(provider.connection, longUser, collateralMint, longUser.publicKey);
+    shortCollateral = await createAssociatedTokenAccount(provider.connection, shortUser, collateralMint, shortUser.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, longCollateral, authority, 10_000_000);
+    await mintTo(provider.connection, authority, collateralMint, shortCollateral, authority, 10_000_000);
+  });
+
+  it("Initialize a scalar pool between 0 and 100", async () => {
+    endTime = Math.floor(Date.now() / 1000) + 10;
+    
+    await createPool(program, authority, marketId, collateralMint, {
+      endTime,
+      marketKind: { scalar: { lowerBound: new anchor.BN(0), upperBound: new anchor.BN(100) } },
+      name: "Temperature",
+      description: "What will the temperature be at noon?",
+      labels: ["LONG", "SHORT"],
+    });
+    
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.marketKind.scalar.lowerBound.toNumber(), 0);
+    assert.equal(poolData.marketKind.scalar.upperBound.toNumber(), 100);
+  });
+
+  it("LONG and SHORT take positions", async () => {
+    for (const [user, userCollateral, outcome, outcomeMint, userOutcomeToken, amount] of [
+      [longUser, longCollateral, LONG, longMint, longToken, 6_000_000],
+      [shortUser, shortCollateral, SHORT, shortMint, shortToken, 4_000_000],
+    ] as const) {
+      await program.methods
+        .mintPredictionTokens(new anchor.BN(amount), outcome)
+        .accounts({
+          user: user.publicKey,
+          pool: poolPda,
+          config: findConfigPda(program.programId),
+          collateralMint,
+          collateralVault,
+          userCollateral,
+          outcomeMint,
+          userOutcomeToken,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("Categorical proposals are rejected", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    try {
+      await program.methods
+        .proposeSolution(LONG)
+        .accounts({ authority: authority.publicKey, pool: poolPda })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("A scalar pool should not accept a winning outcome");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMarketKind");
+    }
+  });
+
+  it("Resolves to 75 and finalizes", async () => {
+    await program.methods
+      .proposeScalarSolution(new anchor.BN(75))
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    const proposed = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(proposed.solutionValue.toNumber(), 75);
+    await waitUntil(provider.connection, proposed.disputePeriodEnd.toNumber());
+    
+    await program.methods
+      .finalizePool()
+      .accounts({ user: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    
+    // 75% of the 10 token pot goes to LONG, the rest to SHORT
+    const poolData = await program.account.bettingPool.fetch(poolPda);
+    assert.equal(poolData.outcomes[LONG].payoutPool.toString(), "7500000");
+    assert.equal(poolData.outcomes[SHORT].payoutPool.toString(), "2500000");
+  });
+
+  it("Both sides claim their linear share", async () => {
+    for (const [user, userCollateral, outcomeMint, userOutcomeToken, expected] of [
+      [longUser, longCollateral, longMint, longToken, "7500000"],
+      [shortUser, shortCollateral, shortMint, shortToken, "2500000"],
+    ] as const) {
+      const before = await getAccount(provider.connection, userCollateral);
+      await program.methods
+        .claimWinnings()
+        .accounts(claimAccounts(user, userCollateral, outcomeMint, userOutcomeToken))
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+      const after = await getAccount(provider.connection, userCollateral);
+      assert.equal((after.amount - before.amount).toString(), expected);
+    }
+    
+    const vault = await getAccount(provider.connection, collateralVault);
+    assert.equal(vault.amount.toString(), "0", "Vault should be empty after both sides claim");
+  });
+});
@@ -0,0 +1,176 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMint,
+  getTransferHook,
+  createMint,
+  createAssociatedTokenAccount,
+  createAssociatedTokenAccountIdempotent,
+  mintTo,
+  transferCheckedWithTransferHook,
+} from '@solana/spl-token';
+import { assert } from "chai";
+import {
+  airdrop,
+  waitUntil,
+  findPoolPda,
+  findVaultPda,
+  findOutcomeMintPda,
+  initializePool,
+  initializeOutcomes,
+  ensureConfig,
+  findConfigPda,
+} from "./helpers";
+
+const YES = 0;
+const NO = 1;
+
+describe("transfer hook", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.Project5Capstone as Program<any>;
+  const hookProgram = anchor.workspace.OutcomeTransferHook as Program<any>;
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+  const other = Keypair.generate();
+
+  const marketId = new anchor.BN(171);
+  const poolPda = findPoolPda(program.programId, authority.publicKey, marketId);
+  const yesMint = findOutcomeMintPda(program.programId, poolPda, YES);
+  const userYes = getAssociatedTokenAddressSync(yesMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const otherYes = getAssociatedTokenAddressSync(yesMint, other.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  let collateralMint: PublicKey;
+  let userCollateral: PublicKey;
+  let endTime: number;
+
+  const findExtraAccountMetasPda = (outcomeMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), outcomeMint.toBuffer()],
+      hookProgram.programId
+    )[0];
+  
+  const enableTransferHook = () =>
+    program.methods
+      .enableTransferHook()
+      .accounts({ authority: authority.publicKey, pool: poolPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  
+  const transferYes = (amount: number) =>
+    transferCheckedWithTransferHook(
+      provider.connection,
+      user,
+      userYes,
+      yesMint,
+      otherYes,
+      user,
+      BigInt(amount),
+      6,
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+  
+  before(async () => {
+    await ensureConfig(program);
+  });
+
+  it("Outcome mints of an opted-in pool call the hook", async () => {
+    await airdrop(provider.connection, authority.publicKey, 2);
+    await airdrop(provider.connection, user.publicKey, 1);
+    await airdrop(provider.connection, other.publicKey, 1);
+    
+    collateralMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    userCollateral = await createAssociatedTokenAccount(provider.connection, user, collateralMint, user.publicKey);
+    await mintTo(provider.connection, authority, collateralMint, userCollateral, authority, 10_000_000);
+    
+    endTime = Math.floor(Date.now() / 1000) + 15;
+    await initializePool(program, authority, marketId, collateralMint, {
+      endTime,
+      disputePeriodSeconds: 60,
+      name: "Hooked pool",
+      description: "Outcome tokens stay put while the answer is pending",
+    });
+    
+    await enableTransferHook();
+    
+    await initializeOutcomes(program, authority, poolPda, collateralMint, ["YES", "NO"], {
+      remainingAccounts: (outcomeMint) => [
+        { pubkey: findExtraAccountMetasPda(outcomeMint), isWritable: true, isSigner: false },
+        { pubkey: hookProgram.programId, isWritable: false, isSigner: false },
+      ],
+    });
+    
+    const mint = await getMint(provider.connection, yesMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    const hook = getTransferHook(mint);
+    assert.ok(hook.programId.equals(hookProgram.programId));
+    assert.ok(hook.authority.equals(poolPda));
+    assert.isNotNull(await provider.connection.getAccountInfo(findExtraAccountMetasPda(yesMint)));
+  });
+
+  it("The hook cannot be enabled once outcomes exist", async () => {
+    try {
+      await enableTransferHook();
+      assert.fail("Existing mints would not call the hook");
+    } catch (error) {
+      assert.include(error.toString(), "TransferHookLocked");
+    }
+  });
+
+  it("Tokens move freely while betting is open", async () => {
+    await program.methods
+      .mintPredictionTokens(new anchor.BN(2_000_000), YES)
+      .accounts({
+        user: user.publicKey,
+        pool: poolPda,
+        config: findConfigPda(program.programId),
+        collateralMint,
+        collateralVault: findVaultPda(program.programId, poolPda),
+        userCollateral,
+        outcomeMint: yesMint,
+        userOutcomeToken: userYes,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    
+    await createAssociatedTokenAccountIdempotent(
+      provider.connection, other, yesMint, other.publicKey, { commitment: "confirmed" }, TOKEN_2022_PROGRAM_ID
+    );
+    await transferYes(500_000);
+    
+    const otherAccount = await getAccount(provider.connection, otherYes, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(otherAccount.amount), 500_000);
+  });
+
+  it("Tokens are held once betting has ended", async () => {
+    await waitUntil(provider.connection, endTime);
+    
+    try {
+      await transferYes(500_000);
+      assert.fail("Transfers are blocked until finalization");
+    } catch (error) {
+      assert.include(error.toString(), "BettingEnded");
+    }
+    
+    const userAccount = await getAccount(provider.connection, userYes, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(userAccount.amount), 1_500_000);
+  });
+});